@@ -0,0 +1,41 @@
+//! Counts the Heap-Allocations of the benchmarked Operations, to show
+//! the Effect of the inline Header-Storage next to the Timings
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Wraps the System-Allocator and counts every Allocation
+pub struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+/// Runs the Function once and prints how many Allocations it needed
+pub fn report<F, R>(name: &str, func: F)
+where
+    F: FnOnce() -> R,
+{
+    let start = ALLOCATIONS.load(Ordering::Relaxed);
+    let result = func();
+    let count = ALLOCATIONS.load(Ordering::Relaxed) - start;
+    drop(result);
+
+    println!("{}: {} allocation(s)", name, count);
+}
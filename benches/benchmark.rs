@@ -1,8 +1,13 @@
 use criterion::{criterion_group, criterion_main};
 
+mod allocations;
+mod headers;
 mod requests;
 mod responses;
 
+#[global_allocator]
+static GLOBAL: allocations::CountingAllocator = allocations::CountingAllocator;
+
 fn generate_headers<'a>(count: usize) -> stream_httparse::Headers<'a> {
     let mut result = stream_httparse::Headers::new();
     for i in 0..count {
@@ -33,6 +38,8 @@ criterion_group!(
     requests::serialize,
     responses::parsing,
     responses::parse_finish,
-    responses::serialize
+    responses::serialize,
+    headers::lookup,
+    headers::build
 );
 criterion_main!(benches);
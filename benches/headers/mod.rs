@@ -0,0 +1,54 @@
+use criterion::{black_box, BenchmarkId, Criterion};
+
+use crate::{allocations, generate_headers};
+
+/// The previous Lookup, which scanned all Headers and compared them
+/// using a full Unicode case-fold, to compare the Index against
+fn linear_caseless_get<'h>(
+    headers: &'h stream_httparse::Headers<'_>,
+    key: &str,
+) -> Option<&'h stream_httparse::header::HeaderValue<'h>> {
+    headers
+        .iter()
+        .find(|pair| caseless::default_caseless_match_str(pair.key.as_ref(), key))
+        .map(|pair| &pair.value)
+}
+
+pub fn lookup(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Headers-Lookup");
+    for header_count in [8, 16, 32, 64, 128usize].iter() {
+        let headers = generate_headers(*header_count);
+        let last_key = format!("KEY-{:03}", header_count - 1);
+
+        group.bench_with_input(
+            BenchmarkId::new("Indexed", header_count),
+            &last_key,
+            |b, key| b.iter(|| headers.get(black_box(key.as_str())).is_some()),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("Linear-Caseless", header_count),
+            &last_key,
+            |b, key| b.iter(|| linear_caseless_get(&headers, black_box(key)).is_some()),
+        );
+    }
+}
+
+pub fn build(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Headers-Build");
+    for header_count in [2, 4, 8, 16, 32, 64, 128usize].iter() {
+        let keys: Vec<String> = (0..*header_count)
+            .map(|i| format!("Key-{:03}", i))
+            .collect();
+
+        let build = || {
+            let mut headers = stream_httparse::Headers::with_capacity(keys.len());
+            for key in keys.iter() {
+                headers.append(key.as_str(), "Value");
+            }
+            headers
+        };
+
+        allocations::report(&format!("Headers-Build/{}", header_count), build);
+        group.bench_function(BenchmarkId::from_parameter(header_count), |b| b.iter(build));
+    }
+}
@@ -1,10 +1,10 @@
 use criterion::{black_box, BenchmarkId, Criterion};
 
-use crate::{generate_header_text, generate_headers};
+use crate::{allocations, generate_header_text, generate_headers};
 
 pub fn parsing(c: &mut Criterion) {
     let mut req_parse_group = c.benchmark_group("HTTP-Request-Parser");
-    for header_count in [2, 4, 8, 16, 32usize].iter() {
+    for header_count in [2, 4, 8, 16, 32, 64usize].iter() {
         let mut req_parse_content = "GET /test HTTP/1.1\r\n".to_owned();
         req_parse_content.push_str(&generate_header_text(*header_count));
         req_parse_content.push_str("\r\n");
@@ -23,7 +23,7 @@ pub fn parsing(c: &mut Criterion) {
 
 pub fn parse_finish(c: &mut Criterion) {
     let mut group = c.benchmark_group("HTTP-Request-Finish");
-    for header_count in [2, 4, 8, 16, 32usize].iter() {
+    for header_count in [2, 4, 8, 16, 32, 64usize].iter() {
         let mut content = "GET /test HTTP/1.1\r\n".to_owned();
         content.push_str(&generate_header_text(*header_count));
         content.push_str("\r\n");
@@ -32,6 +32,9 @@ pub fn parse_finish(c: &mut Criterion) {
         let mut parser = stream_httparse::streaming_parser::ReqParser::new_capacity(2048);
         parser.block_parse(content_bytes);
 
+        allocations::report(&format!("HTTP-Request-Finish/{}", header_count), || {
+            parser.finish()
+        });
         group.bench_function(BenchmarkId::from_parameter(header_count), |b| {
             b.iter(|| parser.finish())
         });
@@ -40,10 +43,10 @@ pub fn parse_finish(c: &mut Criterion) {
 
 pub fn serialize(c: &mut Criterion) {
     let mut group = c.benchmark_group("HTTP-Request-Serialize");
-    for header_count in [2, 4, 8, 16, 32usize].iter() {
+    for header_count in [2, 4, 8, 16, 32, 64usize].iter() {
         let headers = generate_headers(*header_count);
         let req = stream_httparse::Request::new(
-            "HTTP/1.1",
+            stream_httparse::Version::Http11,
             stream_httparse::Method::GET,
             "/path",
             headers.clone(),
@@ -4,7 +4,7 @@ use crate::{generate_header_text, generate_headers};
 
 pub fn parsing(c: &mut Criterion) {
     let mut group = c.benchmark_group("HTTP-Response-Parser");
-    for header_count in [2, 4, 8, 16, 32usize].iter() {
+    for header_count in [2, 4, 8, 16, 32, 64usize].iter() {
         let mut content = "HTTP/1.1 200 OK\r\n".to_owned();
         content.push_str(&generate_header_text(*header_count));
         content.push_str("\r\n");
@@ -23,7 +23,7 @@ pub fn parsing(c: &mut Criterion) {
 
 pub fn parse_finish(c: &mut Criterion) {
     let mut group = c.benchmark_group("HTTP-Response-Finish");
-    for header_count in [2, 4, 8, 16, 32usize].iter() {
+    for header_count in [2, 4, 8, 16, 32, 64usize].iter() {
         let mut content = "HTTP/1.1 200 OK\r\n".to_owned();
         content.push_str(&generate_header_text(*header_count));
         content.push_str("\r\n");
@@ -34,7 +34,7 @@ pub fn parse_finish(c: &mut Criterion) {
             let mut parser = stream_httparse::streaming_parser::RespParser::new_capacity(4096);
             parser.block_parse(black_box(content_bytes));
             b.iter(|| {
-                parser.finish();
+                let _ = parser.finish();
             })
         });
     }
@@ -42,10 +42,10 @@ pub fn parse_finish(c: &mut Criterion) {
 
 pub fn serialize(c: &mut Criterion) {
     let mut group = c.benchmark_group("HTTP-Response-Serialize");
-    for header_count in [2, 4, 8, 16, 32usize].iter() {
+    for header_count in [2, 4, 8, 16, 32, 64usize].iter() {
         let headers = generate_headers(*header_count);
         let resp = stream_httparse::Response::new(
-            "HTTP/1.1",
+            stream_httparse::Version::Http11,
             stream_httparse::StatusCode::OK,
             headers,
             "Random Response Body".as_bytes().to_vec(),
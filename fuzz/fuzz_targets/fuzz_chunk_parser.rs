@@ -10,6 +10,6 @@ fuzz_target!(|data: &[u8]| {
     let (done, _) = chunk_parser.block_parse(data);
 
     if done {
-        chunk_parser.finish();
+        let _ = chunk_parser.finish();
     }
 });
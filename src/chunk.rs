@@ -1,16 +1,100 @@
+use crate::{grammar, Headers};
+
+/// A single Extension that was attached to a Chunk, as
+/// defined in [RFC 9112 7.1.1](https://www.rfc-editor.org/rfc/rfc9112#section-7.1.1)
+#[derive(Debug, PartialEq, Clone)]
+pub struct ChunkExtension {
+    name: String,
+    value: Option<String>,
+}
+
+impl ChunkExtension {
+    /// Creates a new Extension with the given Name and
+    /// optional Value
+    pub fn new<N, V>(name: N, value: Option<V>) -> Self
+    where
+        N: Into<String>,
+        V: Into<String>,
+    {
+        Self {
+            name: name.into(),
+            value: value.map(|v| v.into()),
+        }
+    }
+
+    /// The Name of the Extension
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// The Value of the Extension, if one was set.
+    /// Quoted Values are already unquoted
+    pub fn value(&self) -> Option<&str> {
+        self.value.as_deref()
+    }
+
+    /// Serializes the Extension, including the leading `;`,
+    /// into the given Buffer
+    pub fn serialize(&self, buf: &mut Vec<u8>) {
+        buf.push(b';');
+        buf.extend_from_slice(self.name.as_bytes());
+
+        let value = match &self.value {
+            Some(v) => v,
+            None => return,
+        };
+        buf.push(b'=');
+        if !value.is_empty() && value.bytes().all(grammar::is_tchar) {
+            buf.extend_from_slice(value.as_bytes());
+            return;
+        }
+
+        buf.push(b'"');
+        for byte in value.bytes() {
+            if byte == b'"' || byte == b'\\' {
+                buf.push(b'\\');
+            }
+            buf.push(byte);
+        }
+        buf.push(b'"');
+    }
+}
+
 /// A single HTTP-Chunk used for sending
 /// Data with `Transfer-Encoding: Chunked`
 #[derive(Debug, PartialEq)]
 pub struct Chunk {
     size: usize,
     body: Vec<u8>,
+    extensions: Vec<ChunkExtension>,
+    trailers: Option<Headers<'static>>,
 }
 
 impl Chunk {
     /// Creates a new Chunk with the given Data as its
     /// state
     pub fn new(size: usize, data: Vec<u8>) -> Self {
-        Self { size, body: data }
+        Self {
+            size,
+            body: data,
+            extensions: Vec::new(),
+            trailers: None,
+        }
+    }
+
+    /// Creates the last Chunk, which marks the end of the Body,
+    /// with the given Trailer-Fields
+    pub fn last(trailers: Headers<'static>) -> Self {
+        Self {
+            size: 0,
+            body: Vec::new(),
+            extensions: Vec::new(),
+            trailers: Some(trailers),
+        }
+    }
+
+    /// Adds the given Extension to the Chunk
+    pub fn add_extension(&mut self, extension: ChunkExtension) {
+        self.extensions.push(extension);
     }
 
     /// Serializes the Chunk into the given Buffer
@@ -18,8 +102,14 @@ impl Chunk {
     pub fn serialize(&self, buf: &mut Vec<u8>) {
         let length = format!("{:x}", self.size);
         buf.extend_from_slice(length.as_bytes());
+        for extension in self.extensions.iter() {
+            extension.serialize(buf);
+        }
         buf.extend_from_slice("\r\n".as_bytes());
         buf.extend_from_slice(&self.body);
+        if let Some(trailers) = &self.trailers {
+            trailers.serialize(buf);
+        }
         buf.extend_from_slice("\r\n".as_bytes());
     }
 
@@ -27,6 +117,33 @@ impl Chunk {
     pub fn size(&self) -> usize {
         self.size
     }
+
+    /// The Data contained in the Chunk
+    pub fn body(&self) -> &[u8] {
+        &self.body
+    }
+
+    /// The Extensions that were send along with the Chunk
+    pub fn extensions(&self) -> &[ChunkExtension] {
+        &self.extensions
+    }
+
+    /// Checks if this is the last Chunk, which marks the
+    /// end of the Body
+    pub fn is_last(&self) -> bool {
+        self.size == 0
+    }
+
+    /// The Trailer-Fields that were send after the last
+    /// Chunk, if there were any
+    pub fn trailers(&self) -> Option<&Headers<'static>> {
+        self.trailers.as_ref()
+    }
+
+    /// Takes the Trailer-Fields out of the Chunk
+    pub fn take_trailers(&mut self) -> Option<Headers<'static>> {
+        self.trailers.take()
+    }
 }
 
 #[cfg(test)]
@@ -61,4 +178,34 @@ mod tests {
             buf
         );
     }
+
+    #[test]
+    fn serialize_with_extensions() {
+        let mut tmp = Chunk::new(9, "Developer".as_bytes().to_vec());
+        tmp.add_extension(ChunkExtension::new("name", Some("value")));
+        tmp.add_extension(ChunkExtension::new("quoted", Some("some value")));
+        tmp.add_extension(ChunkExtension::new("flag", None::<String>));
+
+        let mut buf: Vec<u8> = Vec::new();
+        tmp.serialize(&mut buf);
+
+        assert_eq!(
+            "9;name=value;quoted=\"some value\";flag\r\nDeveloper\r\n"
+                .as_bytes()
+                .to_vec(),
+            buf
+        );
+    }
+    #[test]
+    fn serialize_last_with_trailers() {
+        let mut trailers = Headers::new();
+        trailers.append("grpc-status".to_owned(), "0".to_owned());
+        let tmp = Chunk::last(trailers);
+
+        let mut buf: Vec<u8> = Vec::new();
+        tmp.serialize(&mut buf);
+
+        assert!(tmp.is_last());
+        assert_eq!("0\r\ngrpc-status: 0\r\n\r\n".as_bytes().to_vec(), buf);
+    }
 }
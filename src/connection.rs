@@ -0,0 +1,136 @@
+use crate::{grammar, header, Headers, Version};
+
+/// The Parameters of the `Keep-Alive` Header, which a Peer
+/// can use to hint how long it keeps an idle Connection open
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct KeepAlive {
+    /// The Time in seconds an idle Connection will be kept open
+    pub timeout: Option<u64>,
+    /// The maximum Number of Requests that will be send over
+    /// the Connection
+    pub max: Option<u64>,
+}
+
+impl KeepAlive {
+    /// Parses the Value of a `Keep-Alive` Header, unknown
+    /// or malformed Parameters are ignored
+    pub fn parse(raw: &str) -> Self {
+        let mut result = Self::default();
+        for param in raw.split(',') {
+            let (name, value) = match param.split_once('=') {
+                Some((name, value)) => (name.trim(), value.trim().trim_matches('"')),
+                None => continue,
+            };
+
+            if name.eq_ignore_ascii_case("timeout") {
+                result.timeout = value.parse().ok();
+            } else if name.eq_ignore_ascii_case("max") {
+                result.max = value.parse().ok();
+            }
+        }
+        result
+    }
+}
+
+/// Returns all the Tokens listed in the `Connection` Headers
+pub(crate) fn tokens<'h>(headers: &'h Headers<'_>) -> Vec<&'h str> {
+    headers
+        .get_all(header::CONNECTION)
+        .filter_map(|value| value.try_as_str_ref())
+        .flat_map(|value| value.split(','))
+        .map(|token| token.trim())
+        .filter(|token| grammar::is_token(token.as_bytes()))
+        .collect()
+}
+
+/// Checks if the Connection has to be closed after the current
+/// Exchange, as described in
+/// [RFC 9112 9.3](https://www.rfc-editor.org/rfc/rfc9112#section-9.3)
+pub(crate) fn must_close(version: Version, headers: &Headers<'_>) -> bool {
+    let tokens = tokens(headers);
+    let has_token = |name: &str| tokens.iter().any(|t| t.eq_ignore_ascii_case(name));
+
+    if has_token("close") {
+        return true;
+    }
+
+    match version {
+        // HTTP/1.0 Connections are only persistent if it was explicitly requested
+        Version::Http10 => !has_token("keep-alive"),
+        _ => version < Version::Http11,
+    }
+}
+
+/// Returns the Parameters of the `Keep-Alive` Header, if present
+pub(crate) fn keep_alive(headers: &Headers<'_>) -> Option<KeepAlive> {
+    headers
+        .get(header::KEEP_ALIVE)
+        .and_then(|value| value.try_as_str_ref())
+        .map(KeepAlive::parse)
+}
+
+/// Returns the Names of the Hop-by-Hop Headers listed in the
+/// `Connection` Headers, which excludes the `close` Option
+pub(crate) fn hop_by_hop_headers<'h>(headers: &'h Headers<'_>) -> Vec<&'h str> {
+    tokens(headers)
+        .into_iter()
+        .filter(|token| !token.eq_ignore_ascii_case("close"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_tokens() {
+        let mut headers = Headers::new();
+        headers.append("Connection", "keep-alive, Upgrade");
+        headers.append("Connection", " X-Custom ,, ");
+        assert_eq!(vec!["keep-alive", "Upgrade", "X-Custom"], tokens(&headers));
+    }
+
+    #[test]
+    fn must_close_defaults() {
+        let headers = Headers::new();
+        assert!(!must_close(Version::Http11, &headers));
+        assert!(!must_close(Version::Other(2, 0), &headers));
+        assert!(must_close(Version::Http10, &headers));
+        assert!(must_close(Version::Http09, &headers));
+    }
+    #[test]
+    fn must_close_tokens() {
+        let mut headers = Headers::new();
+        headers.set("Connection", "Keep-Alive");
+        assert!(!must_close(Version::Http10, &headers));
+
+        let mut headers = Headers::new();
+        headers.set("Connection", "Upgrade, close");
+        assert!(must_close(Version::Http11, &headers));
+    }
+
+    #[test]
+    fn parse_keep_alive() {
+        assert_eq!(
+            KeepAlive {
+                timeout: Some(5),
+                max: Some(1000)
+            },
+            KeepAlive::parse("timeout=5, max=1000")
+        );
+        assert_eq!(
+            KeepAlive {
+                timeout: Some(10),
+                max: None
+            },
+            KeepAlive::parse("Timeout=\"10\", max=abc, other")
+        );
+    }
+
+    #[test]
+    fn hop_by_hop() {
+        let mut headers = Headers::new();
+        headers.set("Connection", "close, X-Custom, Keep-Alive");
+        assert_eq!(vec!["X-Custom", "Keep-Alive"], hop_by_hop_headers(&headers));
+    }
+}
@@ -1,4 +1,4 @@
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum StringContainer<'a> {
     Ref(&'a str),
     Owned(String),
@@ -8,7 +8,7 @@ impl<'a> AsRef<str> for StringContainer<'a> {
     fn as_ref(&self) -> &str {
         match self {
             Self::Ref(r) => r,
-            Self::Owned(o) => &o,
+            Self::Owned(o) => o,
         }
     }
 }
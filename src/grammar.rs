@@ -0,0 +1,112 @@
+/// Checks if the given Byte is a valid `tchar`, which are the
+/// Characters that can be used in a `token`, as defined in
+/// [RFC 9110 5.6.2](https://www.rfc-editor.org/rfc/rfc9110#section-5.6.2)
+pub(crate) fn is_tchar(byte: u8) -> bool {
+    matches!(
+        byte,
+        b'!' | b'#'
+            | b'$'
+            | b'%'
+            | b'&'
+            | b'\''
+            | b'*'
+            | b'+'
+            | b'-'
+            | b'.'
+            | b'^'
+            | b'_'
+            | b'`'
+            | b'|'
+            | b'~'
+    ) || byte.is_ascii_alphanumeric()
+}
+
+/// Checks if the given Data is a valid `token`
+pub(crate) fn is_token(data: &[u8]) -> bool {
+    !data.is_empty() && data.iter().all(|b| is_tchar(*b))
+}
+
+/// Checks if the given Byte is whitespace (SP or HTAB)
+pub(crate) fn is_whitespace(byte: u8) -> bool {
+    byte == b' ' || byte == b'\t'
+}
+
+/// Checks if the given Data is a valid `field-value`, which can
+/// contain any visible Character, obs-text and whitespace but no
+/// other Control-Characters, as defined in
+/// [RFC 9110 5.5](https://www.rfc-editor.org/rfc/rfc9110#section-5.5)
+pub(crate) fn is_field_value(data: &[u8]) -> bool {
+    data.iter()
+        .all(|b| is_whitespace(*b) || (*b >= 0x21 && *b != 0x7F))
+}
+
+/// Removes any leading and trailing whitespace (SP or HTAB)
+pub(crate) fn trim_whitespace(mut data: &[u8]) -> &[u8] {
+    while let [first, rest @ ..] = data {
+        if !is_whitespace(*first) {
+            break;
+        }
+        data = rest;
+    }
+    while let [rest @ .., last] = data {
+        if !is_whitespace(*last) {
+            break;
+        }
+        data = rest;
+    }
+    data
+}
+
+/// Removes any leading and trailing whitespace (SP or HTAB) from
+/// the given Range in the Data and returns the trimmed Range
+pub(crate) fn trim_whitespace_range(
+    data: &[u8],
+    (mut start, mut end): (usize, usize),
+) -> (usize, usize) {
+    while start < end && is_whitespace(data[start]) {
+        start += 1;
+    }
+    while start < end && is_whitespace(data[end - 1]) {
+        end -= 1;
+    }
+    (start, end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_valid() {
+        assert!(is_token(b"Content-Length"));
+        assert!(is_token(b"x-custom_header.1"));
+    }
+    #[test]
+    fn token_invalid() {
+        assert!(!is_token(b""));
+        assert!(!is_token(b"Content Length"));
+        assert!(!is_token(b"key:"));
+        assert!(!is_token(b"\"quoted\""));
+    }
+
+    #[test]
+    fn trim() {
+        assert_eq!(b"value", trim_whitespace(b" \tvalue \t"));
+        assert_eq!(b"some value", trim_whitespace(b"some value"));
+        assert_eq!(b"", trim_whitespace(b"  "));
+    }
+    #[test]
+    fn trim_range() {
+        assert_eq!((4, 9), trim_whitespace_range(b"a: \tvalue \r\n", (2, 10)));
+        assert_eq!((4, 4), trim_whitespace_range(b"a:  ", (2, 4)));
+    }
+    #[test]
+    fn field_value() {
+        assert!(is_field_value(b"some value\twith tabs"));
+        assert!(is_field_value(b"obs-text \xE4"));
+        assert!(is_field_value(b""));
+        assert!(!is_field_value(b"null\0byte"));
+        assert!(!is_field_value(b"line\nbreak"));
+        assert!(!is_field_value(b"delete\x7F"));
+    }
+}
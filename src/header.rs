@@ -2,6 +2,12 @@ mod key;
 pub use key::HeaderKey;
 mod value;
 pub use value::HeaderValue;
+mod standard;
+pub use standard::*;
+mod case;
+pub use case::HeaderCase;
+mod error;
+pub use error::HeaderError;
 
 /// A single HTTP-Header Pair(Key-Value)
 #[derive(Clone, Debug)]
@@ -12,6 +18,15 @@ pub struct Header<'a> {
     pub value: HeaderValue<'a>,
 }
 
+impl Default for Header<'_> {
+    fn default() -> Self {
+        Self {
+            key: HeaderKey::StrRef(""),
+            value: HeaderValue::StrRef(""),
+        }
+    }
+}
+
 impl PartialEq for Header<'_> {
     fn eq(&self, other: &Self) -> bool {
         self.key.eq(&other.key)
@@ -28,9 +43,30 @@ impl<'a> Header<'a> {
         buf.extend_from_slice("\r\n".as_bytes());
     }
 
+    /// Serializes the Header into the given Buffer, while writing
+    /// the Key in the given Case
+    pub fn serialize_with_case(&self, case: HeaderCase, buf: &mut Vec<u8>) {
+        self.key.serialize_with_case(case, buf);
+        buf.extend_from_slice(": ".as_bytes());
+        self.value.serialize(buf);
+        buf.extend_from_slice("\r\n".as_bytes());
+    }
+
+    /// Checks that the Key is a valid Token and the Value a valid
+    /// Field-Value, so that the Header can be serialized safely
+    pub fn validate(&self) -> Result<(), HeaderError> {
+        if !self.key.is_valid() {
+            return Err(HeaderError::InvalidName);
+        }
+        if !self.value.is_valid() {
+            return Err(HeaderError::InvalidValue);
+        }
+        Ok(())
+    }
+
     /// Clones all the assosicated Data to create a completly
     /// new and independant Header instance
-    pub fn to_owned<'refed, 'owned>(&'refed self) -> Header<'owned> {
+    pub fn to_owned<'owned>(&self) -> Header<'owned> {
         Header {
             key: self.key.to_owned(),
             value: self.value.to_owned(),
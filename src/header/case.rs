@@ -0,0 +1,55 @@
+/// Determines how the Names of Headers are written when they
+/// are serialized
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HeaderCase {
+    /// Writes the Names exactly as they are stored
+    #[default]
+    Preserve,
+    /// Writes the Names in Title-Case, like `Content-Type`, which
+    /// uses the canonical Form for all the registered Headers
+    Title,
+    /// Writes the Names in lowercase, like `content-type`
+    Lower,
+}
+
+impl HeaderCase {
+    /// Converts a single Byte of a Name into this Case, based on
+    /// the previous Byte of the Name
+    pub(crate) fn convert(self, byte: u8, previous: Option<u8>) -> u8 {
+        match self {
+            Self::Preserve => byte,
+            Self::Lower => byte.to_ascii_lowercase(),
+            Self::Title => match previous {
+                None | Some(b'-') => byte.to_ascii_uppercase(),
+                Some(_) => byte.to_ascii_lowercase(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert(case: HeaderCase, name: &str) -> String {
+        let mut previous = None;
+        name.bytes()
+            .map(|byte| {
+                let result = case.convert(byte, previous);
+                previous = Some(byte);
+                result as char
+            })
+            .collect()
+    }
+
+    #[test]
+    fn convert_name() {
+        assert_eq!(
+            "x-CUSTOM-name",
+            convert(HeaderCase::Preserve, "x-CUSTOM-name")
+        );
+        assert_eq!("X-Custom-Name", convert(HeaderCase::Title, "x-CUSTOM-name"));
+        assert_eq!("x-custom-name", convert(HeaderCase::Lower, "x-CUSTOM-name"));
+        assert_eq!("X--B3", convert(HeaderCase::Title, "x--b3"));
+    }
+}
@@ -0,0 +1,29 @@
+use crate::streaming_parser::ParseError;
+
+/// The Errors that can occur when adding a Header that could not
+/// be serialized as a valid HTTP-Header
+#[derive(Debug, Clone, PartialEq)]
+pub enum HeaderError {
+    /// The Name is not a valid Token
+    InvalidName,
+    /// The Value contains Control-Characters, like CR or LF
+    InvalidValue,
+}
+
+impl std::fmt::Display for HeaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            Self::InvalidName => write!(f, "Invalid Header-Name"),
+            Self::InvalidValue => write!(f, "Invalid Header-Value"),
+        }
+    }
+}
+
+impl From<HeaderError> for ParseError {
+    fn from(err: HeaderError) -> Self {
+        match err {
+            HeaderError::InvalidName => ParseError::InvalidHeaderName,
+            HeaderError::InvalidValue => ParseError::InvalidHeaderValue,
+        }
+    }
+}
@@ -1,9 +1,15 @@
-use std::cmp::Ordering;
+use std::{
+    cmp::Ordering,
+    hash::{Hash, Hasher},
+};
+
+use super::{HeaderCase, StandardHeader};
+use crate::grammar;
 
 /// Allows the HeaderKey to take the form of a variety of different
 /// valid Types, mostly related to their lifetimes.
 /// This however also gives more control over how they are compared
-/// to each other, ignoring case in this case
+/// to each other, ignoring ASCII-case in this case
 ///
 /// ```rust
 /// use stream_httparse::header::HeaderKey;
@@ -16,6 +22,9 @@ pub enum HeaderKey<'a> {
     StrRef(&'a str),
     /// Stores the Key as an owned String
     Str(String),
+    /// One of the registered Header-Names, which is cheaper to
+    /// store and compare
+    Standard(StandardHeader),
 }
 
 impl<'a> From<&'a str> for HeaderKey<'a> {
@@ -28,28 +37,92 @@ impl<'a> From<String> for HeaderKey<'a> {
         HeaderKey::Str(val)
     }
 }
+impl<'a> From<StandardHeader> for HeaderKey<'a> {
+    fn from(val: StandardHeader) -> Self {
+        HeaderKey::Standard(val)
+    }
+}
 
 impl<'a> HeaderKey<'a> {
+    /// Creates the Key for a Name received by one of the Parsers, which
+    /// uses the registered Header if the Name is in its canonical Form,
+    /// to keep the original Casing intact
+    pub(crate) fn parsed(raw: &'a str) -> Self {
+        match StandardHeader::from_canonical(raw) {
+            Some(header) => HeaderKey::Standard(header),
+            None => HeaderKey::StrRef(raw),
+        }
+    }
+
+    /// The owned Version of `parsed`, which only allocates if the
+    /// Name is not a registered Header
+    pub(crate) fn parsed_owned<'owned>(raw: &str) -> HeaderKey<'owned> {
+        match StandardHeader::from_canonical(raw) {
+            Some(header) => HeaderKey::Standard(header),
+            None => HeaderKey::Str(raw.to_owned()),
+        }
+    }
+
+    /// Checks if the Key is a valid Token, as defined in
+    /// [RFC 9110 5.1](https://www.rfc-editor.org/rfc/rfc9110#section-5.1)
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Self::Standard(_) => true,
+            _ => grammar::is_token(self.as_ref().as_bytes()),
+        }
+    }
+
     /// Serializes the Key into the Buffer by appending
     /// the Data to it
     pub fn serialize(&self, buf: &mut Vec<u8>) {
         match *self {
-            Self::StrRef(ref value) => {
+            Self::StrRef(value) => {
                 buf.extend_from_slice(value.as_bytes());
             }
             Self::Str(ref value) => {
                 buf.extend_from_slice(value.as_bytes());
             }
+            Self::Standard(header) => {
+                buf.extend_from_slice(header.name().as_bytes());
+            }
         }
     }
 
+    /// Serializes the Key in the given Case into the Buffer
+    /// by appending the Data to it
+    pub fn serialize_with_case(&self, case: HeaderCase, buf: &mut Vec<u8>) {
+        match case {
+            HeaderCase::Preserve => self.serialize(buf),
+            _ => {
+                buf.reserve(self.as_ref().len());
+                buf.extend(self.cased(case));
+            }
+        };
+    }
+
+    /// Returns the Bytes of the Key in the given Case
+    pub(crate) fn cased(&self, case: HeaderCase) -> impl Iterator<Item = u8> + '_ {
+        // The registered Headers already have a canonical Title-Case
+        let (name, case) = match (self, case) {
+            (Self::Standard(header), HeaderCase::Title) => (header.name(), HeaderCase::Preserve),
+            _ => (self.as_ref(), case),
+        };
+
+        name.bytes().scan(None, move |previous, byte| {
+            let result = case.convert(byte, *previous);
+            *previous = Some(byte);
+            Some(result)
+        })
+    }
+
     /// Clones all the needed Data in order to create a new
     /// HeaderKey that is completly independant of the given
     /// self reference
-    pub fn to_owned<'refed, 'owned>(&'refed self) -> HeaderKey<'owned> {
+    pub fn to_owned<'owned>(&self) -> HeaderKey<'owned> {
         let value = match self {
             Self::StrRef(tmp) => tmp.to_string(),
             Self::Str(tmp) => tmp.to_owned(),
+            Self::Standard(header) => return HeaderKey::Standard(*header),
         };
 
         HeaderKey::Str(value)
@@ -59,23 +132,54 @@ impl<'a> HeaderKey<'a> {
 impl AsRef<str> for HeaderKey<'_> {
     fn as_ref(&self) -> &str {
         match *self {
-            Self::Str(ref value) => &value,
-            Self::StrRef(ref value) => value,
+            Self::Str(ref value) => value,
+            Self::StrRef(value) => value,
+            Self::Standard(header) => header.name(),
         }
     }
 }
 
 impl PartialEq for HeaderKey<'_> {
     fn eq(&self, other: &Self) -> bool {
-        caseless::default_caseless_match_str(self.as_ref(), other.as_ref())
+        if let (Self::Standard(own), Self::Standard(other)) = (self, other) {
+            return own == other;
+        }
+
+        // Header-Names are Tokens, so they only ever contain ASCII
+        self.as_ref().eq_ignore_ascii_case(other.as_ref())
     }
 }
 
 impl Eq for HeaderKey<'_> {}
 
+impl Hash for HeaderKey<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Setting the 0x20 Bit in every Byte turns all ASCII-Letters
+        // into lowercase, which keeps the Hash consistent with the
+        // case-insensitive Equality, while hashing a whole Word at once
+        const LOWERCASE: u64 = 0x2020_2020_2020_2020;
+
+        let bytes = self.as_ref().as_bytes();
+        let mut words = bytes.chunks_exact(8);
+        for word in &mut words {
+            let mut buffer = [0; 8];
+            buffer.copy_from_slice(word);
+            state.write_u64(u64::from_le_bytes(buffer) | LOWERCASE);
+        }
+
+        // The last Byte of the final Word is always padding, so it can
+        // hold the Length instead of hashing it separately
+        let remainder = words.remainder();
+        let mut buffer = [0; 8];
+        buffer[..remainder.len()].copy_from_slice(remainder);
+        buffer[7] = bytes.len() as u8;
+        state.write_u64(u64::from_le_bytes(buffer) | LOWERCASE);
+    }
+}
+
 impl PartialOrd for HeaderKey<'_> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.as_ref().partial_cmp(other.as_ref())
+        Some(self.cmp(other))
     }
 }
 
@@ -96,6 +200,47 @@ mod tests {
         assert_eq!(HeaderKey::StrRef("TeSt"), HeaderKey::StrRef("test"));
     }
 
+    #[test]
+    fn hash_ignore_case() {
+        use std::collections::hash_map::DefaultHasher;
+
+        let hash = |key: HeaderKey| {
+            let mut hasher = DefaultHasher::new();
+            key.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        let long_key = "X-Some-Really-Long-Header-Name-That-Spans-Chunks";
+        assert_eq!(
+            hash(HeaderKey::StrRef(long_key)),
+            hash(HeaderKey::Str(long_key.to_uppercase()))
+        );
+        assert_ne!(hash(HeaderKey::StrRef("ab")), hash(HeaderKey::StrRef("a")));
+    }
+
+    #[test]
+    fn equals_standard() {
+        let standard = HeaderKey::Standard(StandardHeader::ContentType);
+        assert_eq!(standard, HeaderKey::StrRef("content-type"));
+        assert_eq!(HeaderKey::Str("CONTENT-TYPE".to_owned()), standard);
+        assert_ne!(standard, HeaderKey::Standard(StandardHeader::ContentLength));
+    }
+    #[test]
+    fn parsed_keeps_case() {
+        assert_eq!(
+            HeaderKey::Standard(StandardHeader::Host),
+            HeaderKey::parsed("Host")
+        );
+        assert!(matches!(
+            HeaderKey::parsed("host"),
+            HeaderKey::StrRef("host")
+        ));
+        assert!(matches!(
+            HeaderKey::parsed_owned("Accept"),
+            HeaderKey::Standard(StandardHeader::Accept)
+        ));
+    }
+
     #[test]
     fn serialize_str() {
         let mut result: Vec<u8> = Vec::new();
@@ -118,4 +263,28 @@ mod tests {
             HeaderKey::StrRef("first") < HeaderKey::StrRef("second")
         );
     }
+
+    #[test]
+    fn serialize_with_case() {
+        let serialize = |key: HeaderKey, case| {
+            let mut buf = Vec::new();
+            key.serialize_with_case(case, &mut buf);
+            String::from_utf8(buf).unwrap()
+        };
+
+        assert_eq!(
+            "www-AUTH",
+            serialize("www-AUTH".into(), HeaderCase::Preserve)
+        );
+        assert_eq!("Www-Auth", serialize("www-AUTH".into(), HeaderCase::Title));
+        assert_eq!("www-auth", serialize("www-AUTH".into(), HeaderCase::Lower));
+        assert_eq!(
+            "WWW-Authenticate",
+            serialize(StandardHeader::WwwAuthenticate.into(), HeaderCase::Title)
+        );
+        assert_eq!(
+            "www-authenticate",
+            serialize(StandardHeader::WwwAuthenticate.into(), HeaderCase::Lower)
+        );
+    }
 }
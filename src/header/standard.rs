@@ -0,0 +1,282 @@
+use super::HeaderKey;
+use std::cmp::Ordering;
+
+macro_rules! standard_headers {
+    ($(($variant:ident, $constant:ident, $name:literal);)+) => {
+        /// The Names of the registered Header-Fields from the
+        /// [IANA HTTP Field Name Registry](https://www.iana.org/assignments/http-fields),
+        /// which can be compared a lot faster than arbitrary Names
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum StandardHeader {
+            $(
+                #[doc = concat!("The `", $name, "` Header")]
+                $variant,
+            )+
+        }
+
+        impl StandardHeader {
+            const ALL: &'static [StandardHeader] = &[$(StandardHeader::$variant,)+];
+
+            /// Returns the Name of the Header in its canonical Form
+            pub fn name(&self) -> &'static str {
+                match *self {
+                    $(StandardHeader::$variant => $name,)+
+                }
+            }
+
+            /// Returns the Header that exactly matches the given
+            /// Name in its canonical Form
+            pub(crate) fn from_canonical(raw: &str) -> Option<Self> {
+                match raw {
+                    $($name => Some(StandardHeader::$variant),)+
+                    _ => None,
+                }
+            }
+        }
+
+        $(
+            #[doc = concat!("The Key for the `", $name, "` Header")]
+            pub const $constant: HeaderKey<'static> = HeaderKey::Standard(StandardHeader::$variant);
+        )+
+    };
+}
+
+// The Headers are sorted by their Name, ignoring its case, so
+// that non-canonical Names can be found using a binary search
+standard_headers! {
+    (AIm, A_IM, "A-IM");
+    (Accept, ACCEPT, "Accept");
+    (AcceptCh, ACCEPT_CH, "Accept-CH");
+    (AcceptCharset, ACCEPT_CHARSET, "Accept-Charset");
+    (AcceptEncoding, ACCEPT_ENCODING, "Accept-Encoding");
+    (AcceptLanguage, ACCEPT_LANGUAGE, "Accept-Language");
+    (AcceptPatch, ACCEPT_PATCH, "Accept-Patch");
+    (AcceptPost, ACCEPT_POST, "Accept-Post");
+    (AcceptRanges, ACCEPT_RANGES, "Accept-Ranges");
+    (AccessControlAllowCredentials, ACCESS_CONTROL_ALLOW_CREDENTIALS, "Access-Control-Allow-Credentials");
+    (AccessControlAllowHeaders, ACCESS_CONTROL_ALLOW_HEADERS, "Access-Control-Allow-Headers");
+    (AccessControlAllowMethods, ACCESS_CONTROL_ALLOW_METHODS, "Access-Control-Allow-Methods");
+    (AccessControlAllowOrigin, ACCESS_CONTROL_ALLOW_ORIGIN, "Access-Control-Allow-Origin");
+    (AccessControlExposeHeaders, ACCESS_CONTROL_EXPOSE_HEADERS, "Access-Control-Expose-Headers");
+    (AccessControlMaxAge, ACCESS_CONTROL_MAX_AGE, "Access-Control-Max-Age");
+    (AccessControlRequestHeaders, ACCESS_CONTROL_REQUEST_HEADERS, "Access-Control-Request-Headers");
+    (AccessControlRequestMethod, ACCESS_CONTROL_REQUEST_METHOD, "Access-Control-Request-Method");
+    (Age, AGE, "Age");
+    (Allow, ALLOW, "Allow");
+    (Alpn, ALPN, "ALPN");
+    (AltSvc, ALT_SVC, "Alt-Svc");
+    (AltUsed, ALT_USED, "Alt-Used");
+    (AuthenticationInfo, AUTHENTICATION_INFO, "Authentication-Info");
+    (Authorization, AUTHORIZATION, "Authorization");
+    (CacheControl, CACHE_CONTROL, "Cache-Control");
+    (CacheStatus, CACHE_STATUS, "Cache-Status");
+    (CdnCacheControl, CDN_CACHE_CONTROL, "CDN-Cache-Control");
+    (ClearSiteData, CLEAR_SITE_DATA, "Clear-Site-Data");
+    (Connection, CONNECTION, "Connection");
+    (ContentDigest, CONTENT_DIGEST, "Content-Digest");
+    (ContentDisposition, CONTENT_DISPOSITION, "Content-Disposition");
+    (ContentEncoding, CONTENT_ENCODING, "Content-Encoding");
+    (ContentLanguage, CONTENT_LANGUAGE, "Content-Language");
+    (ContentLength, CONTENT_LENGTH, "Content-Length");
+    (ContentLocation, CONTENT_LOCATION, "Content-Location");
+    (ContentRange, CONTENT_RANGE, "Content-Range");
+    (ContentSecurityPolicy, CONTENT_SECURITY_POLICY, "Content-Security-Policy");
+    (ContentSecurityPolicyReportOnly, CONTENT_SECURITY_POLICY_REPORT_ONLY, "Content-Security-Policy-Report-Only");
+    (ContentType, CONTENT_TYPE, "Content-Type");
+    (Cookie, COOKIE, "Cookie");
+    (CrossOriginEmbedderPolicy, CROSS_ORIGIN_EMBEDDER_POLICY, "Cross-Origin-Embedder-Policy");
+    (CrossOriginOpenerPolicy, CROSS_ORIGIN_OPENER_POLICY, "Cross-Origin-Opener-Policy");
+    (CrossOriginResourcePolicy, CROSS_ORIGIN_RESOURCE_POLICY, "Cross-Origin-Resource-Policy");
+    (Date, DATE, "Date");
+    (DPoP, DPOP, "DPoP");
+    (EarlyData, EARLY_DATA, "Early-Data");
+    (ETag, ETAG, "ETag");
+    (Expect, EXPECT, "Expect");
+    (Expires, EXPIRES, "Expires");
+    (Forwarded, FORWARDED, "Forwarded");
+    (From, FROM, "From");
+    (Host, HOST, "Host");
+    (IfMatch, IF_MATCH, "If-Match");
+    (IfModifiedSince, IF_MODIFIED_SINCE, "If-Modified-Since");
+    (IfNoneMatch, IF_NONE_MATCH, "If-None-Match");
+    (IfRange, IF_RANGE, "If-Range");
+    (IfUnmodifiedSince, IF_UNMODIFIED_SINCE, "If-Unmodified-Since");
+    (KeepAlive, KEEP_ALIVE, "Keep-Alive");
+    (LastModified, LAST_MODIFIED, "Last-Modified");
+    (Link, LINK, "Link");
+    (Location, LOCATION, "Location");
+    (MaxForwards, MAX_FORWARDS, "Max-Forwards");
+    (Origin, ORIGIN, "Origin");
+    (Pragma, PRAGMA, "Pragma");
+    (Prefer, PREFER, "Prefer");
+    (PreferenceApplied, PREFERENCE_APPLIED, "Preference-Applied");
+    (Priority, PRIORITY, "Priority");
+    (ProxyAuthenticate, PROXY_AUTHENTICATE, "Proxy-Authenticate");
+    (ProxyAuthenticationInfo, PROXY_AUTHENTICATION_INFO, "Proxy-Authentication-Info");
+    (ProxyAuthorization, PROXY_AUTHORIZATION, "Proxy-Authorization");
+    (ProxyStatus, PROXY_STATUS, "Proxy-Status");
+    (Range, RANGE, "Range");
+    (Referer, REFERER, "Referer");
+    (ReferrerPolicy, REFERRER_POLICY, "Referrer-Policy");
+    (Refresh, REFRESH, "Refresh");
+    (ReprDigest, REPR_DIGEST, "Repr-Digest");
+    (RetryAfter, RETRY_AFTER, "Retry-After");
+    (SecWebSocketAccept, SEC_WEBSOCKET_ACCEPT, "Sec-WebSocket-Accept");
+    (SecWebSocketExtensions, SEC_WEBSOCKET_EXTENSIONS, "Sec-WebSocket-Extensions");
+    (SecWebSocketKey, SEC_WEBSOCKET_KEY, "Sec-WebSocket-Key");
+    (SecWebSocketProtocol, SEC_WEBSOCKET_PROTOCOL, "Sec-WebSocket-Protocol");
+    (SecWebSocketVersion, SEC_WEBSOCKET_VERSION, "Sec-WebSocket-Version");
+    (Server, SERVER, "Server");
+    (ServerTiming, SERVER_TIMING, "Server-Timing");
+    (SetCookie, SET_COOKIE, "Set-Cookie");
+    (StrictTransportSecurity, STRICT_TRANSPORT_SECURITY, "Strict-Transport-Security");
+    (Te, TE, "TE");
+    (TimingAllowOrigin, TIMING_ALLOW_ORIGIN, "Timing-Allow-Origin");
+    (Trailer, TRAILER, "Trailer");
+    (TransferEncoding, TRANSFER_ENCODING, "Transfer-Encoding");
+    (Upgrade, UPGRADE, "Upgrade");
+    (UserAgent, USER_AGENT, "User-Agent");
+    (Vary, VARY, "Vary");
+    (Via, VIA, "Via");
+    (WantContentDigest, WANT_CONTENT_DIGEST, "Want-Content-Digest");
+    (WantReprDigest, WANT_REPR_DIGEST, "Want-Repr-Digest");
+    (Warning, WARNING, "Warning");
+    (WwwAuthenticate, WWW_AUTHENTICATE, "WWW-Authenticate");
+    (XContentTypeOptions, X_CONTENT_TYPE_OPTIONS, "X-Content-Type-Options");
+    (XFrameOptions, X_FRAME_OPTIONS, "X-Frame-Options");
+}
+
+impl StandardHeader {
+    /// Returns the Header that matches the given Name, ignoring
+    /// its case, or None if the Name is not registered
+    pub fn from_name(raw: &str) -> Option<Self> {
+        Self::from_canonical(raw).or_else(|| {
+            Self::ALL
+                .binary_search_by(|header| cmp_ignore_case(header.name(), raw))
+                .ok()
+                .map(|index| Self::ALL[index])
+        })
+    }
+
+    /// Checks if the Header may only be send once in a Message, so
+    /// that duplicates of it are rejected by the Parsers
+    pub fn is_singleton(&self) -> bool {
+        matches!(
+            self,
+            Self::Authorization | Self::ContentLength | Self::ContentType | Self::Host
+        )
+    }
+
+    /// Checks if the Value of the Header is defined as a comma-separated
+    /// List, so that multiple Lines can be combined into a single one, as
+    /// described in [RFC 9110 5.3](https://www.rfc-editor.org/rfc/rfc9110#section-5.3)
+    ///
+    /// `Set-Cookie` is never a List, as its Values may contain Commas
+    pub fn is_list(&self) -> bool {
+        matches!(
+            self,
+            Self::Accept
+                | Self::AcceptCharset
+                | Self::AcceptEncoding
+                | Self::AcceptLanguage
+                | Self::AcceptPatch
+                | Self::AcceptRanges
+                | Self::AccessControlAllowHeaders
+                | Self::AccessControlAllowMethods
+                | Self::AccessControlExposeHeaders
+                | Self::AccessControlRequestHeaders
+                | Self::Allow
+                | Self::AltSvc
+                | Self::CacheControl
+                | Self::CacheStatus
+                | Self::ClearSiteData
+                | Self::Connection
+                | Self::ContentEncoding
+                | Self::ContentLanguage
+                | Self::Expect
+                | Self::Forwarded
+                | Self::IfMatch
+                | Self::IfNoneMatch
+                | Self::KeepAlive
+                | Self::Link
+                | Self::Pragma
+                | Self::Prefer
+                | Self::PreferenceApplied
+                | Self::ProxyAuthenticate
+                | Self::ProxyStatus
+                | Self::SecWebSocketExtensions
+                | Self::SecWebSocketProtocol
+                | Self::ServerTiming
+                | Self::Te
+                | Self::TimingAllowOrigin
+                | Self::Trailer
+                | Self::TransferEncoding
+                | Self::Upgrade
+                | Self::Vary
+                | Self::Via
+                | Self::Warning
+                | Self::WwwAuthenticate
+        )
+    }
+}
+
+impl std::fmt::Display for StandardHeader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Compares the two Names byte by byte, while ignoring the ASCII-Case
+fn cmp_ignore_case(first: &str, second: &str) -> Ordering {
+    let first = first.bytes().map(|b| b.to_ascii_lowercase());
+    let second = second.bytes().map(|b| b.to_ascii_lowercase());
+    first.cmp(second)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_name() {
+        assert_eq!(
+            Some(StandardHeader::ContentLength),
+            StandardHeader::from_name("Content-Length")
+        );
+        assert_eq!(
+            Some(StandardHeader::WwwAuthenticate),
+            StandardHeader::from_name("www-authenticate")
+        );
+        assert_eq!(None, StandardHeader::from_name("X-Custom"));
+
+        for header in StandardHeader::ALL {
+            let lower = header.name().to_ascii_lowercase();
+            assert_eq!(Some(*header), StandardHeader::from_name(&lower));
+        }
+    }
+    #[test]
+    fn sorted_by_name() {
+        for pair in StandardHeader::ALL.windows(2) {
+            assert_eq!(
+                Ordering::Less,
+                cmp_ignore_case(pair[0].name(), pair[1].name()),
+                "{} is not sorted before {}",
+                pair[0].name(),
+                pair[1].name()
+            );
+        }
+    }
+
+    #[test]
+    fn names_roundtrip() {
+        for header in StandardHeader::ALL {
+            assert_eq!(Some(*header), StandardHeader::from_canonical(header.name()));
+        }
+    }
+
+    #[test]
+    fn constants() {
+        assert_eq!(HeaderKey::StrRef("transfer-encoding"), TRANSFER_ENCODING);
+        assert_eq!("Sec-WebSocket-Key", SEC_WEBSOCKET_KEY.as_ref());
+    }
+}
@@ -1,3 +1,7 @@
+use std::borrow::Cow;
+
+use crate::grammar;
+
 /// A single HeaderValue that can hold Data
 /// in a variety of forms allowing for easier
 /// and more flexible use
@@ -9,6 +13,11 @@ pub enum HeaderValue<'a> {
     Str(String),
     /// Stores the Value in its raw Number format
     NumberUsize(usize),
+    /// Stores the Value as a reference to raw Bytes, which is used
+    /// for Values that contain obs-text and are not valid UTF-8
+    BytesRef(&'a [u8]),
+    /// Stores the Value as owned raw Bytes
+    Bytes(Vec<u8>),
 }
 
 impl<'a> From<&'a str> for HeaderValue<'a> {
@@ -26,13 +35,23 @@ impl<'a> From<usize> for HeaderValue<'a> {
         HeaderValue::NumberUsize(val)
     }
 }
+impl<'a> From<&'a [u8]> for HeaderValue<'a> {
+    fn from(val: &'a [u8]) -> Self {
+        HeaderValue::BytesRef(val)
+    }
+}
+impl<'a> From<Vec<u8>> for HeaderValue<'a> {
+    fn from(val: Vec<u8>) -> Self {
+        HeaderValue::Bytes(val)
+    }
+}
 
 impl<'a> HeaderValue<'a> {
     /// Serializes the Value into the given Buffer by
     /// appending the Data to it
     pub fn serialize(&self, buf: &mut Vec<u8>) {
         match *self {
-            Self::StrRef(ref value) => {
+            Self::StrRef(value) => {
                 buf.extend_from_slice(value.as_bytes());
             }
             Self::Str(ref value) => {
@@ -41,16 +60,35 @@ impl<'a> HeaderValue<'a> {
             Self::NumberUsize(ref value) => {
                 buf.extend_from_slice(value.to_string().as_bytes());
             }
+            Self::BytesRef(value) => {
+                buf.extend_from_slice(value);
+            }
+            Self::Bytes(ref value) => {
+                buf.extend_from_slice(value);
+            }
+        }
+    }
+
+    /// Checks if the Value is a valid Field-Value, which means it
+    /// does not contain any Control-Characters like CR or LF
+    pub fn is_valid(&self) -> bool {
+        match self.try_as_bytes() {
+            Some(value) => grammar::is_field_value(value),
+            None => true,
         }
     }
 
     /// Turns the given Value, regardless of how it is stored,
     /// into an owned String
+    ///
+    /// Bytes that are not valid UTF-8 are decoded as Latin-1
+    #[allow(clippy::inherent_to_string)]
     pub fn to_string(&self) -> String {
         match *self {
             Self::StrRef(ref value) => value.to_string(),
             Self::Str(ref value) => value.clone(),
             Self::NumberUsize(ref value) => value.to_string(),
+            Self::BytesRef(_) | Self::Bytes(_) => self.to_text().into_owned(),
         }
     }
 
@@ -73,12 +111,56 @@ impl<'a> HeaderValue<'a> {
     }
 
     /// Tries to return a reference to the underlying String,
-    /// if it is a String, otherwise returns None
+    /// if it is a String or Bytes that are valid UTF-8, otherwise
+    /// returns None
     pub fn try_as_str_ref(&self) -> Option<&str> {
         match self {
             Self::StrRef(value) => Some(value),
-            Self::Str(value) => Some(&value),
+            Self::Str(value) => Some(value),
             Self::NumberUsize(_) => None,
+            Self::BytesRef(value) => std::str::from_utf8(value).ok(),
+            Self::Bytes(value) => std::str::from_utf8(value).ok(),
+        }
+    }
+
+    /// Returns a reference to the underlying Bytes, if it is stored
+    /// as a String or as Bytes, otherwise returns None
+    pub fn try_as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Self::StrRef(value) => Some(value.as_bytes()),
+            Self::Str(value) => Some(value.as_bytes()),
+            Self::NumberUsize(_) => None,
+            Self::BytesRef(value) => Some(value),
+            Self::Bytes(value) => Some(value),
+        }
+    }
+
+    /// Decodes the Value as Latin-1 (ISO-8859-1), which maps every
+    /// Byte to a Character and therefore never fails, as described in
+    /// [RFC 9110 5.5](https://www.rfc-editor.org/rfc/rfc9110#section-5.5)
+    /// for obs-text
+    ///
+    /// This always decodes the Bytes that are send for the Value, so a
+    /// String with non-ASCII Characters is decoded Byte by Byte as well,
+    /// just like the same Value stored as Bytes
+    pub fn as_latin1(&self) -> Cow<'_, str> {
+        let bytes = match self.try_as_bytes() {
+            Some(bytes) => bytes,
+            None => return Cow::Owned(self.to_string()),
+        };
+
+        match std::str::from_utf8(bytes) {
+            Ok(value) if bytes.is_ascii() => Cow::Borrowed(value),
+            _ => Cow::Owned(bytes.iter().map(|b| char::from(*b)).collect()),
+        }
+    }
+
+    /// Decodes the Value as UTF-8 if possible and falls back
+    /// to Latin-1 otherwise
+    pub fn to_text(&self) -> Cow<'_, str> {
+        match self.try_as_str_ref() {
+            Some(value) => Cow::Borrowed(value),
+            None => self.as_latin1(),
         }
     }
 
@@ -88,6 +170,8 @@ impl<'a> HeaderValue<'a> {
         match self {
             Self::Str(tmp) => tmp.len(),
             Self::StrRef(tmp) => tmp.len(),
+            Self::BytesRef(tmp) => tmp.len(),
+            Self::Bytes(tmp) => tmp.len(),
             Self::NumberUsize(val) => {
                 let mut tmp = *val;
                 let mut result = 1;
@@ -116,25 +200,35 @@ impl<'a> HeaderValue<'a> {
     /// Clones all the needed Data in order to create a new
     /// HeaderValue that is completly independant of the given
     /// self reference
-    pub fn to_owned<'refed, 'owned>(&'refed self) -> HeaderValue<'owned> {
+    pub fn to_owned<'owned>(&self) -> HeaderValue<'owned> {
         match self {
             Self::Str(tmp) => HeaderValue::Str(tmp.clone()),
             Self::StrRef(tmp) => HeaderValue::Str(tmp.to_string()),
             Self::NumberUsize(tmp) => HeaderValue::NumberUsize(*tmp),
+            Self::BytesRef(tmp) => HeaderValue::Bytes(tmp.to_vec()),
+            Self::Bytes(tmp) => HeaderValue::Bytes(tmp.clone()),
         }
     }
 }
 
-impl PartialEq<std::string::String> for HeaderValue<'_> {
-    fn eq(&self, other: &std::string::String) -> bool {
+impl PartialEq<str> for HeaderValue<'_> {
+    fn eq(&self, other: &str) -> bool {
         match *self {
-            Self::StrRef(ref value) => value == other,
+            Self::StrRef(value) => value == other,
             Self::Str(ref value) => value == other,
-            _ => false,
+            Self::BytesRef(value) => value == other.as_bytes(),
+            Self::Bytes(ref value) => value == other.as_bytes(),
+            Self::NumberUsize(_) => false,
         }
     }
 }
 
+impl PartialEq<std::string::String> for HeaderValue<'_> {
+    fn eq(&self, other: &std::string::String) -> bool {
+        self == other.as_str()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -163,17 +257,63 @@ mod tests {
 
     #[test]
     fn equals_ignore_case() {
+        assert!(HeaderValue::StrRef("test").eq_ignore_case(&HeaderValue::StrRef("TEST")));
+        assert!(HeaderValue::StrRef("test").eq_ignore_case(&HeaderValue::StrRef("test")));
+        assert!(HeaderValue::StrRef("TeSt").eq_ignore_case(&HeaderValue::StrRef("test")));
+    }
+
+    #[test]
+    fn serialize_bytes() {
+        let mut result: Vec<u8> = Vec::new();
+        HeaderValue::BytesRef(b"na\xefve").serialize(&mut result);
+        HeaderValue::Bytes(b"-\xff".to_vec()).serialize(&mut result);
+
+        assert_eq!(b"na\xefve-\xff", &result[..]);
+    }
+
+    #[test]
+    fn decode_bytes() {
+        let latin1 = HeaderValue::BytesRef(b"attachment; filename=\"na\xefve.txt\"");
+        assert_eq!(None, latin1.try_as_str_ref());
+        assert_eq!("attachment; filename=\"naïve.txt\"", latin1.as_latin1());
+        assert_eq!("attachment; filename=\"naïve.txt\"", latin1.to_text());
         assert_eq!(
-            true,
-            HeaderValue::StrRef("test").eq_ignore_case(&HeaderValue::StrRef("TEST"))
+            HeaderValue::Bytes(b"attachment; filename=\"na\xefve.txt\"".to_vec()),
+            latin1.to_owned()
         );
+
+        let utf8 = HeaderValue::Bytes("naïve".as_bytes().to_vec());
+        assert_eq!(Some("naïve"), utf8.try_as_str_ref());
+        assert_eq!("naïve", utf8.to_text());
+        assert_eq!("na\u{c3}\u{af}ve", utf8.as_latin1());
+
+        // Strings follow the same Rule as Bytes
+        assert_eq!("na\u{c3}\u{af}ve", HeaderValue::StrRef("naïve").as_latin1());
         assert_eq!(
-            true,
-            HeaderValue::StrRef("test").eq_ignore_case(&HeaderValue::StrRef("test"))
+            "na\u{c3}\u{af}ve",
+            HeaderValue::Str("naïve".to_owned()).as_latin1()
         );
+        assert_eq!("ascii", HeaderValue::StrRef("ascii").as_latin1());
+        assert_eq!("12", HeaderValue::NumberUsize(12).as_latin1());
+
         assert_eq!(
-            true,
-            HeaderValue::StrRef("TeSt").eq_ignore_case(&HeaderValue::StrRef("test"))
+            Some("ascii".as_bytes()),
+            HeaderValue::StrRef("ascii").try_as_bytes()
         );
+        assert_eq!(None, HeaderValue::NumberUsize(1).try_as_bytes());
+    }
+
+    #[test]
+    fn equals_string() {
+        let expected = "naïve".to_owned();
+        assert!(HeaderValue::StrRef("naïve") == expected);
+        assert!(HeaderValue::Str("naïve".to_owned()) == expected);
+        assert!(HeaderValue::BytesRef("naïve".as_bytes()) == expected);
+        assert!(HeaderValue::Bytes("naïve".as_bytes().to_vec()) == expected);
+        assert!(HeaderValue::BytesRef(b"na\xefve") != expected);
+        let number = "1".to_owned();
+        assert!(HeaderValue::NumberUsize(1) != number);
+
+        assert!(HeaderValue::BytesRef(b"value") == *"value");
     }
 }
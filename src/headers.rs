@@ -1,13 +1,33 @@
+use std::{borrow::Cow, sync::OnceLock};
+
 use crate::{
-    header::{HeaderKey, HeaderValue},
+    header::{HeaderCase, HeaderError, HeaderKey, HeaderValue},
+    inline_vec::{self, InlineVec},
     Header,
 };
 
+mod index;
+use index::HeaderIndex;
+
+/// The Number of Headers that are stored inline, before they
+/// are moved to the Heap
+const INLINE_HEADERS: usize = 16;
+
+/// The Number of Headers above which Lookups go through an Index,
+/// instead of simply scanning all the Headers
+const INDEX_THRESHOLD: usize = 16;
+
 /// A collection of Headers
-#[derive(Debug, PartialEq, Clone)]
+///
+/// The first couple of Headers are stored inline, without any
+/// Heap-Allocation. Once the Collection holds more than that, the first
+/// Lookup also builds an Index from every Key to its first Position,
+/// to keep Lookups fast for large Sets of Headers
+#[derive(Debug, Clone)]
 pub struct Headers<'a> {
-    headers: Vec<Header<'a>>,
+    headers: InlineVec<Header<'a>, INLINE_HEADERS>,
     max_value_length: usize,
+    index: OnceLock<HeaderIndex>,
 }
 
 impl<'a> Headers<'a> {
@@ -16,20 +36,23 @@ impl<'a> Headers<'a> {
     /// as that would avoid frequent reallocations
     pub fn new() -> Self {
         Self {
-            headers: Vec::new(),
+            headers: InlineVec::new(),
             max_value_length: 0,
+            index: OnceLock::new(),
         }
     }
 
     /// Creates the Headers-Object with the given Capacity
-    /// prereserved for future Headers.
+    /// prereserved for future Headers, which only allocates if
+    /// the Capacity does not fit inline.
     /// This should be used when you already kind of know
     /// how many Headers this will hold, as it will avoid
     /// extra allocations in the future
     pub fn with_capacity(cap: usize) -> Self {
         Self {
-            headers: Vec::with_capacity(cap),
+            headers: InlineVec::with_capacity(cap),
             max_value_length: 0,
+            index: OnceLock::new(),
         }
     }
 
@@ -49,20 +72,63 @@ impl<'a> Headers<'a> {
         let final_key = key.into();
         if let Some(index) = self.find(&final_key) {
             self.headers.remove(index);
+            self.reset_index();
         }
 
         let n_value: HeaderValue = value.into();
-        let n_value_length = n_value.length();
-        if n_value_length > self.max_value_length {
-            self.max_value_length = n_value_length;
-        }
+        self.track_value_length(&n_value);
 
-        self.headers.push(Header {
+        self.push(Header {
             key: final_key,
             value: n_value,
         });
     }
 
+    /// Replaces all the Headers for the given Key with a single
+    /// Header with the given Value
+    ///
+    /// ## Behaviour
+    /// The new Header takes the Place of the first matching Header,
+    /// so the Order of the Collection is kept, and all the other
+    /// matching Headers are removed.
+    /// If the Key is not present, the new Header is added to the End
+    /// of the Collection
+    pub fn replace_all<K, V>(&mut self, key: K, value: V)
+    where
+        K: Into<HeaderKey<'a>>,
+        V: Into<HeaderValue<'a>>,
+    {
+        let n_value: HeaderValue = value.into();
+        self.track_value_length(&n_value);
+
+        let n_header = Header {
+            key: key.into(),
+            value: n_value,
+        };
+        let position = match self.find(&n_header.key) {
+            Some(position) => position,
+            None => {
+                self.push(n_header);
+                return;
+            }
+        };
+
+        // There are no matching Headers before the first one, so only
+        // the ones after it need to be removed
+        let previous_len = self.headers.len();
+        let mut current = 0;
+        self.headers.retain(|pair| {
+            let keep = current <= position || pair.key != n_header.key;
+            current += 1;
+            keep
+        });
+        if self.headers.len() != previous_len {
+            self.reset_index();
+        }
+
+        self.headers[position] = n_header;
+    }
+
     /// Appends the given Key-Value Pair to the end of the
     /// Collection, without checking if the Key is already
     /// present in the Collection
@@ -72,18 +138,80 @@ impl<'a> Headers<'a> {
         V: Into<HeaderValue<'a>>,
     {
         let n_value: HeaderValue = value.into();
-        let n_value_length = n_value.length();
-        if n_value_length > self.max_value_length {
-            self.max_value_length = n_value_length;
-        }
+        self.track_value_length(&n_value);
 
-        self.headers.push(Header {
+        self.push(Header {
             key: key.into(),
             value: n_value,
         })
     }
 
+    /// Sets the Key-Value Pair like [`set`](Self::set), after checking
+    /// that the Key is a valid Token and the Value a valid Field-Value,
+    /// so that a Value with a CRLF can not inject additional Headers
+    pub fn try_set<K, V>(&mut self, key: K, value: V) -> Result<(), HeaderError>
+    where
+        K: Into<HeaderKey<'a>>,
+        V: Into<HeaderValue<'a>>,
+    {
+        let header = Header {
+            key: key.into(),
+            value: value.into(),
+        };
+        header.validate()?;
+
+        self.set(header.key, header.value);
+        Ok(())
+    }
+
+    /// Appends the Key-Value Pair like [`append`](Self::append), after
+    /// checking that the Key is a valid Token and the Value a valid
+    /// Field-Value
+    pub fn try_append<K, V>(&mut self, key: K, value: V) -> Result<(), HeaderError>
+    where
+        K: Into<HeaderKey<'a>>,
+        V: Into<HeaderValue<'a>>,
+    {
+        let header = Header {
+            key: key.into(),
+            value: value.into(),
+        };
+        header.validate()?;
+
+        self.track_value_length(&header.value);
+        self.push(header);
+        Ok(())
+    }
+
+    fn push(&mut self, header: Header<'a>) {
+        let position = self.headers.len();
+        self.headers.push(header);
+
+        // The Index is only kept up to date once it has been built
+        if let Some(index) = self.index.get_mut() {
+            index.insert(&self.headers, position);
+        }
+    }
+
+    /// Drops the Index after the Positions of the Headers changed,
+    /// so it will be rebuilt on the next Lookup
+    fn reset_index(&mut self) {
+        self.index = OnceLock::new();
+    }
+
+    fn track_value_length(&mut self, value: &HeaderValue<'_>) {
+        let n_value_length = value.length();
+        if n_value_length > self.max_value_length {
+            self.max_value_length = n_value_length;
+        }
+    }
+
     fn find(&self, key: &HeaderKey<'a>) -> Option<usize> {
+        if self.headers.len() > INDEX_THRESHOLD {
+            let index = self.index.get_or_init(|| HeaderIndex::build(&self.headers));
+            return index.get(&self.headers, key);
+        }
+
         for (index, pair) in self.headers.iter().enumerate() {
             if &pair.key == key {
                 return Some(index);
@@ -100,16 +228,28 @@ impl<'a> Headers<'a> {
     {
         if let Some(index) = self.find(&key.into()) {
             self.headers.remove(index);
+            self.reset_index();
         }
     }
 
+    /// Removes all the Headers, that match the given Key,
+    /// from the Collection
+    pub fn remove_all<K>(&mut self, key: K)
+    where
+        K: Into<HeaderKey<'a>>,
+    {
+        let key = key.into();
+        self.headers.retain(|pair| pair.key != key);
+        self.reset_index();
+    }
+
     /// Searches the Collection for a Header that matches
     /// the given Key
     ///
     /// Returns:
     /// * None: if no Header matches the Key
     /// * A Reference to the underlying Header-Value that
-    /// belongs to the Key
+    ///   belongs to the Key
     pub fn get<K>(&self, key: K) -> Option<&HeaderValue<'a>>
     where
         K: Into<HeaderKey<'a>>,
@@ -118,6 +258,76 @@ impl<'a> Headers<'a> {
             .map(|index| &self.headers.get(index).unwrap().value)
     }
 
+    /// Returns the Values of all the Headers that match
+    /// the given Key, in the Order they were added
+    pub fn get_all<K>(&self, key: K) -> impl Iterator<Item = &HeaderValue<'a>>
+    where
+        K: Into<HeaderKey<'a>>,
+    {
+        let key = key.into();
+        self.headers
+            .iter()
+            .filter(move |pair| pair.key == key)
+            .map(|pair| &pair.value)
+    }
+
+    /// Returns the Values of all the Headers that match the
+    /// given Key combined into a single comma-separated Value,
+    /// as described in
+    /// [RFC 9110 5.3](https://www.rfc-editor.org/rfc/rfc9110#section-5.3)
+    ///
+    /// This should only be used for Headers that are defined as
+    /// Lists, as it would for example break `Set-Cookie`
+    pub fn get_combined<K>(&self, key: K) -> Option<Cow<'_, str>>
+    where
+        K: Into<HeaderKey<'a>>,
+    {
+        let mut values = self.get_all(key);
+        let first = values.next()?;
+
+        let mut result = match values.next() {
+            None => {
+                return Some(match first.try_as_str_ref() {
+                    Some(value) => Cow::Borrowed(value),
+                    None => Cow::Owned(first.to_string()),
+                });
+            }
+            Some(second) => {
+                let mut result = first.to_string();
+                result.push_str(", ");
+                result.push_str(&second.to_string());
+                result
+            }
+        };
+        for value in values {
+            result.push_str(", ");
+            result.push_str(&value.to_string());
+        }
+        Some(Cow::Owned(result))
+    }
+
+    /// Returns the Entry for the given Key, which allows for
+    /// modifying the Collection based on whether or not the
+    /// Key is already present
+    pub fn entry<K>(&mut self, key: K) -> HeaderEntry<'_, 'a>
+    where
+        K: Into<HeaderKey<'a>>,
+    {
+        let key = key.into();
+        let index = self.find(&key);
+        HeaderEntry {
+            headers: self,
+            key,
+            index,
+        }
+    }
+
+    /// Returns an Iterator over all the Headers in the Order
+    /// they were added
+    pub fn iter(&self) -> std::slice::Iter<'_, Header<'a>> {
+        self.headers.iter()
+    }
+
     /// Serializes the Collection of Headers into the
     /// given Buffer by append to it
     pub fn serialize(&self, buf: &mut Vec<u8>) {
@@ -126,6 +336,29 @@ impl<'a> Headers<'a> {
         }
     }
 
+    /// Checks that every Header in the Collection can be serialized
+    /// safely, see [`Header::validate`]
+    pub fn validate(&self) -> Result<(), HeaderError> {
+        self.headers.iter().try_for_each(Header::validate)
+    }
+
+    /// Serializes the Collection of Headers into the given Buffer,
+    /// but only if all of them are valid, otherwise nothing is
+    /// written to the Buffer
+    pub fn try_serialize(&self, buf: &mut Vec<u8>) -> Result<(), HeaderError> {
+        self.validate()?;
+        self.serialize(buf);
+        Ok(())
+    }
+
+    /// Serializes the Collection of Headers into the given Buffer,
+    /// while writing all the Keys in the given Case
+    pub fn serialize_with_case(&self, case: HeaderCase, buf: &mut Vec<u8>) {
+        for pair in self.headers.iter() {
+            pair.serialize_with_case(case, buf);
+        }
+    }
+
     /// Returns the Size in bytes of the biggest Value as text.
     ///
     /// This means that all the Header-Values in this collection
@@ -141,8 +374,8 @@ impl<'a> Headers<'a> {
 
     /// Clones all the assosicated Data to produce a new and
     /// independant Header-Collection
-    pub fn to_owned<'refed, 'owned>(&'refed self) -> Headers<'owned> {
-        let mut n_headers = Vec::with_capacity(self.headers.len());
+    pub fn to_owned<'owned>(&self) -> Headers<'owned> {
+        let mut n_headers = InlineVec::with_capacity(self.headers.len());
 
         for tmp in self.headers.iter() {
             n_headers.push(tmp.to_owned());
@@ -151,16 +384,147 @@ impl<'a> Headers<'a> {
         Headers {
             headers: n_headers,
             max_value_length: self.max_value_length,
+            index: OnceLock::new(),
         }
     }
 }
 
+impl PartialEq for Headers<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        // The Index only depends on the Headers themselves
+        self.headers == other.headers && self.max_value_length == other.max_value_length
+    }
+}
+
 impl<'a> Default for Headers<'a> {
     fn default() -> Self {
         Self::new()
     }
 }
 
+impl<'a> IntoIterator for Headers<'a> {
+    type Item = Header<'a>;
+    type IntoIter = HeadersIntoIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        HeadersIntoIter {
+            headers: self.headers.into_iter(),
+        }
+    }
+}
+
+/// An Iterator that moves all the Headers out of a Collection,
+/// obtained using `Headers::into_iter`
+#[derive(Debug)]
+pub struct HeadersIntoIter<'a> {
+    headers: inline_vec::IntoIter<Header<'a>, INLINE_HEADERS>,
+}
+
+impl<'a> Iterator for HeadersIntoIter<'a> {
+    type Item = Header<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.headers.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.headers.size_hint()
+    }
+}
+
+impl<'a> DoubleEndedIterator for HeadersIntoIter<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.headers.next_back()
+    }
+}
+
+impl<'a> ExactSizeIterator for HeadersIntoIter<'a> {}
+
+impl<'h, 'a> IntoIterator for &'h Headers<'a> {
+    type Item = &'h Header<'a>;
+    type IntoIter = std::slice::Iter<'h, Header<'a>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.headers.iter()
+    }
+}
+
+/// A single Key in a Header-Collection, which may or may not
+/// be present, obtained using `Headers::entry`
+#[derive(Debug)]
+pub struct HeaderEntry<'h, 'a> {
+    headers: &'h mut Headers<'a>,
+    key: HeaderKey<'a>,
+    index: Option<usize>,
+}
+
+impl<'h, 'a> HeaderEntry<'h, 'a> {
+    /// Returns the Key of the Entry
+    pub fn key(&self) -> &HeaderKey<'a> {
+        &self.key
+    }
+
+    /// Checks if a Header with the Key is present in the Collection
+    pub fn is_present(&self) -> bool {
+        self.index.is_some()
+    }
+
+    /// Returns the Value of the first Header with the Key, after
+    /// inserting the given Value if the Key was not present
+    pub fn or_insert<V>(self, value: V) -> &'h mut HeaderValue<'a>
+    where
+        V: Into<HeaderValue<'a>>,
+    {
+        self.or_insert_with(|| value)
+    }
+
+    /// Returns the Value of the first Header with the Key, after
+    /// inserting the Value returned by the Function if the Key was
+    /// not present
+    pub fn or_insert_with<F, V>(self, func: F) -> &'h mut HeaderValue<'a>
+    where
+        F: FnOnce() -> V,
+        V: Into<HeaderValue<'a>>,
+    {
+        let index = match self.index {
+            Some(index) => index,
+            None => {
+                self.headers.append(self.key, func());
+                self.headers.headers.len() - 1
+            }
+        };
+        &mut self.headers.headers[index].value
+    }
+
+    /// Appends the given Item to the comma-separated List in the
+    /// Value of the first Header with the Key, or inserts the Item
+    /// as the Value if the Key was not present
+    pub fn append_to_list<V>(self, item: V) -> &'h mut HeaderValue<'a>
+    where
+        V: Into<HeaderValue<'a>>,
+    {
+        let index = match self.index {
+            Some(index) => index,
+            None => return self.or_insert(item),
+        };
+
+        // Combining the raw Bytes keeps any obs-text in the Values intact
+        let mut value = Vec::new();
+        self.headers.headers[index].value.serialize(&mut value);
+        value.extend_from_slice(b", ");
+        item.into().serialize(&mut value);
+
+        let value = match String::from_utf8(value) {
+            Ok(value) => HeaderValue::Str(value),
+            Err(e) => HeaderValue::Bytes(e.into_bytes()),
+        };
+        self.headers.track_value_length(&value);
+        self.headers.headers[index].value = value;
+
+        &mut self.headers.headers[index].value
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -200,6 +564,55 @@ mod tests {
             headers.headers
         );
     }
+    #[test]
+    fn headers_set_with_duplicates() {
+        let mut headers = Headers::new();
+        headers.append("Via", "1.1 first");
+        headers.append("Other", "value");
+        headers.append("via", "1.1 second");
+
+        headers.set("Via", "1.1 proxy");
+
+        let pairs: Vec<(&str, &HeaderValue)> = headers
+            .iter()
+            .map(|header| (header.key.as_ref(), &header.value))
+            .collect();
+        assert_eq!(
+            vec![
+                ("Other", &HeaderValue::StrRef("value")),
+                ("via", &HeaderValue::StrRef("1.1 second")),
+                ("Via", &HeaderValue::StrRef("1.1 proxy")),
+            ],
+            pairs
+        );
+    }
+    #[test]
+    fn headers_replace_all() {
+        let mut headers = Headers::new();
+        headers.append("Via", "1.1 first");
+        headers.append("Other", "value");
+        headers.append("via", "1.1 second");
+        headers.append("Last", "value");
+        headers.append("VIA", "1.1 third");
+
+        headers.replace_all("Via", "1.1 proxy");
+        headers.replace_all("New", "value");
+
+        let pairs: Vec<(&str, &HeaderValue)> = headers
+            .iter()
+            .map(|header| (header.key.as_ref(), &header.value))
+            .collect();
+        assert_eq!(
+            vec![
+                ("Via", &HeaderValue::StrRef("1.1 proxy")),
+                ("Other", &HeaderValue::StrRef("value")),
+                ("Last", &HeaderValue::StrRef("value")),
+                ("New", &HeaderValue::StrRef("value")),
+            ],
+            pairs
+        );
+        assert_eq!(Some(&HeaderValue::StrRef("value")), headers.get("new"));
+    }
 
     #[test]
     fn headers_remove_existing() {
@@ -274,6 +687,139 @@ mod tests {
         assert_eq!(None, headers.get("other-key"));
     }
 
+    #[test]
+    fn headers_inline() {
+        let mut headers = Headers::with_capacity(INLINE_HEADERS);
+        for i in 0..INLINE_HEADERS {
+            headers.append(format!("Key-{}", i), i);
+        }
+        assert!(headers.headers.is_inline());
+
+        headers.append("Spilled", "value");
+        assert!(!headers.headers.is_inline());
+        assert_eq!(Some(&HeaderValue::NumberUsize(0)), headers.get("Key-0"));
+        assert!(!Headers::with_capacity(INLINE_HEADERS + 1)
+            .headers
+            .is_inline());
+    }
+    #[test]
+    fn headers_indexed() {
+        let mut headers = Headers::new();
+        for i in 0..INDEX_THRESHOLD {
+            headers.append(format!("Key-{}", i), i);
+        }
+        assert_eq!(Some(&HeaderValue::NumberUsize(3)), headers.get("KEY-3"));
+        assert!(headers.index.get().is_none());
+
+        headers.append("Duplicate", "first");
+        assert!(headers.index.get().is_none());
+        assert_eq!(Some(&HeaderValue::NumberUsize(3)), headers.get("KEY-3"));
+        assert!(headers.index.get().is_some());
+
+        headers.append("duplicate", "second");
+        assert_eq!(
+            Some(&HeaderValue::StrRef("first")),
+            headers.get("DUPLICATE")
+        );
+
+        headers.remove("Key-0");
+        assert_eq!(None, headers.get("Key-0"));
+        assert_eq!(
+            Some(&HeaderValue::StrRef("first")),
+            headers.get("Duplicate")
+        );
+
+        headers.set("Key-1", "updated");
+        assert_eq!(Some(&HeaderValue::StrRef("updated")), headers.get("key-1"));
+        assert_eq!(Some(&HeaderValue::NumberUsize(2)), headers.get("key-2"));
+
+        headers.remove_all("Duplicate");
+        assert!(headers.index.get().is_none());
+        assert_eq!(None, headers.get("Duplicate"));
+        assert_eq!(Some(&HeaderValue::NumberUsize(15)), headers.get("Key-15"));
+    }
+    #[test]
+    fn headers_get_all() {
+        let mut headers = Headers::new();
+        headers.append("Set-Cookie", "a=1");
+        headers.append("Other", "value");
+        headers.append("set-cookie", "b=2");
+
+        assert_eq!(
+            vec![&HeaderValue::StrRef("a=1"), &HeaderValue::StrRef("b=2")],
+            headers.get_all("Set-Cookie").collect::<Vec<_>>()
+        );
+        assert_eq!(0, headers.get_all("Missing").count());
+    }
+    #[test]
+    fn headers_remove_all() {
+        let mut headers = Headers::new();
+        headers.append("Via", "1.1 first");
+        headers.append("Other", "value");
+        headers.append("via", "1.1 second");
+
+        headers.remove_all("Via");
+        assert_eq!(
+            vec![Header {
+                key: HeaderKey::StrRef("Other"),
+                value: HeaderValue::StrRef("value")
+            }],
+            headers.headers
+        );
+    }
+    #[test]
+    fn headers_get_combined() {
+        let mut headers = Headers::new();
+        headers.append("Accept", "text/html");
+        assert_eq!(
+            Some(Cow::Borrowed("text/html")),
+            headers.get_combined("Accept")
+        );
+
+        headers.append("Accept", "application/json");
+        headers.append("Accept-Length", 12);
+        headers.append("accept", 13);
+        assert_eq!(
+            Some("text/html, application/json, 13"),
+            headers.get_combined("Accept").as_deref()
+        );
+        assert_eq!(None, headers.get_combined("Missing"));
+    }
+    #[test]
+    fn headers_entry() {
+        let mut headers = Headers::new();
+        assert!(!headers.entry("Vary").is_present());
+
+        headers.entry("Vary").or_insert("Origin");
+        headers.entry("Vary").or_insert("Ignored");
+        assert_eq!(Some(&HeaderValue::StrRef("Origin")), headers.get("Vary"));
+
+        headers.entry("vary").append_to_list("Accept-Encoding");
+        headers.entry("Cache-Control").append_to_list("no-cache");
+        assert_eq!(
+            Some(&HeaderValue::Str("Origin, Accept-Encoding".to_owned())),
+            headers.get("Vary")
+        );
+        assert_eq!(
+            Some(&HeaderValue::StrRef("no-cache")),
+            headers.get("Cache-Control")
+        );
+        assert_eq!(23, headers.get_max_value_size());
+    }
+    #[test]
+    fn headers_iter() {
+        let mut headers = Headers::new();
+        headers.append("First", "1");
+        headers.append("Second", "2");
+
+        let keys: Vec<&str> = headers.iter().map(|h| h.key.as_ref()).collect();
+        assert_eq!(vec!["First", "Second"], keys);
+        assert_eq!(2, (&headers).into_iter().count());
+
+        let owned: Vec<Header> = headers.into_iter().collect();
+        assert_eq!(HeaderValue::StrRef("2"), owned[1].value);
+    }
+
     #[test]
     fn headers_serialize() {
         let mut headers = Headers::new();
@@ -292,4 +838,44 @@ mod tests {
         headers.serialize(&mut tmp);
         assert_eq!(result, &tmp);
     }
+
+    #[test]
+    fn try_set_and_append() {
+        let mut headers = Headers::new();
+        assert_eq!(Ok(()), headers.try_set("Location", "/next"));
+        assert_eq!(
+            Err(HeaderError::InvalidValue),
+            headers.try_set("Location", "/next\r\nSet-Cookie: injected=1")
+        );
+        assert_eq!(
+            Err(HeaderError::InvalidName),
+            headers.try_append("Bad Name", "value")
+        );
+        assert_eq!(
+            Err(HeaderError::InvalidName),
+            headers.try_append("", "value")
+        );
+        assert_eq!(Ok(()), headers.try_append("Content-Length", 10));
+
+        assert_eq!(2, headers.get_header_count());
+        assert_eq!(Some(&HeaderValue::StrRef("/next")), headers.get("Location"));
+    }
+
+    #[test]
+    fn try_serialize() {
+        let mut headers = Headers::new();
+        headers.set("Location", "/next");
+
+        let mut buf = Vec::new();
+        assert_eq!(Ok(()), headers.try_serialize(&mut buf));
+        assert_eq!("Location: /next\r\n".as_bytes(), &buf);
+
+        headers.append("X-Injected", "value\nX-Other: 1");
+        let mut buf = Vec::new();
+        assert_eq!(
+            Err(HeaderError::InvalidValue),
+            headers.try_serialize(&mut buf)
+        );
+        assert!(buf.is_empty());
+    }
 }
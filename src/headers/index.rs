@@ -0,0 +1,118 @@
+use std::{collections::hash_map::RandomState, hash::BuildHasher};
+
+use crate::{header::HeaderKey, Header};
+
+/// An Index from every Key to the Position of the first Header with
+/// that Key, which is stored as an Open-Addressing Table of Positions,
+/// so it does not need to hold any of the Keys itself
+#[derive(Debug, Clone)]
+pub(crate) struct HeaderIndex {
+    hasher: RandomState,
+    /// The Position of the Header + 1, where 0 marks an empty Slot
+    slots: Vec<usize>,
+    len: usize,
+}
+
+impl HeaderIndex {
+    /// Builds the Index for all the given Headers
+    pub fn build(headers: &[Header<'_>]) -> Self {
+        let mut index = Self {
+            hasher: RandomState::new(),
+            slots: vec![0; (headers.len() * 2).next_power_of_two()],
+            len: 0,
+        };
+        for position in 0..headers.len() {
+            index.insert(headers, position);
+        }
+        index
+    }
+
+    fn hash(&self, key: &HeaderKey<'_>) -> usize {
+        self.hasher.hash_one(key) as usize
+    }
+
+    /// Adds the Header at the given Position to the Index, unless an
+    /// earlier Header with the same Key is already present
+    pub fn insert(&mut self, headers: &[Header<'_>], position: usize) {
+        if (self.len + 1) * 2 > self.slots.len() {
+            let mut grown = Self {
+                hasher: self.hasher.clone(),
+                slots: vec![0; self.slots.len() * 2],
+                len: 0,
+            };
+            for previous in 0..position {
+                grown.insert(headers, previous);
+            }
+            *self = grown;
+        }
+
+        let key = &headers[position].key;
+        let mask = self.slots.len() - 1;
+        let mut slot = self.hash(key) & mask;
+        loop {
+            match self.slots[slot] {
+                0 => {
+                    self.slots[slot] = position + 1;
+                    self.len += 1;
+                    return;
+                }
+                existing if headers[existing - 1].key == *key => return,
+                _ => slot = (slot + 1) & mask,
+            };
+        }
+    }
+
+    /// Returns the Position of the first Header with the given Key
+    pub fn get(&self, headers: &[Header<'_>], key: &HeaderKey<'_>) -> Option<usize> {
+        let mask = self.slots.len() - 1;
+        let mut slot = self.hash(key) & mask;
+        loop {
+            match self.slots[slot] {
+                0 => return None,
+                existing if headers[existing - 1].key == *key => return Some(existing - 1),
+                _ => slot = (slot + 1) & mask,
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(keys: &[&'static str]) -> Vec<Header<'static>> {
+        keys.iter()
+            .map(|key| Header {
+                key: HeaderKey::StrRef(key),
+                value: "value".into(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn first_position() {
+        let headers = headers(&["First", "Second", "first", "Third"]);
+        let index = HeaderIndex::build(&headers);
+
+        assert_eq!(Some(0), index.get(&headers, &HeaderKey::StrRef("FIRST")));
+        assert_eq!(Some(3), index.get(&headers, &HeaderKey::StrRef("third")));
+        assert_eq!(None, index.get(&headers, &HeaderKey::StrRef("Fourth")));
+    }
+
+    #[test]
+    fn grows() {
+        let mut headers = headers(&["Key"]);
+        let mut index = HeaderIndex::build(&headers);
+        for i in 0..64 {
+            headers.push(Header {
+                key: HeaderKey::Str(format!("Key-{}", i)),
+                value: i.into(),
+            });
+            index.insert(&headers, headers.len() - 1);
+        }
+
+        for (position, header) in headers.iter().enumerate() {
+            assert_eq!(Some(position), index.get(&headers, &header.key));
+        }
+    }
+}
@@ -0,0 +1,306 @@
+use std::{
+    iter::Take,
+    ops::{Deref, DerefMut},
+};
+
+/// A Vec-like Collection that stores the first `N` Elements inline,
+/// without any Heap-Allocation, and only moves them to the Heap once
+/// more Elements are added.
+///
+/// The unused inline Slots are filled with Default-Values, which
+/// avoids the need for any unsafe Code
+pub(crate) enum InlineVec<T, const N: usize> {
+    Inline { len: usize, items: [T; N] },
+    Heap(Vec<T>),
+}
+
+impl<T, const N: usize> InlineVec<T, N>
+where
+    T: Default,
+{
+    /// Creates a new empty Collection, that stores its
+    /// Elements inline
+    pub fn new() -> Self {
+        Self::Inline {
+            len: 0,
+            items: std::array::from_fn(|_| T::default()),
+        }
+    }
+
+    /// Creates a new empty Collection, that can hold at least the
+    /// given Number of Elements without reallocating
+    pub fn with_capacity(cap: usize) -> Self {
+        if cap <= N {
+            Self::new()
+        } else {
+            Self::Heap(Vec::with_capacity(cap))
+        }
+    }
+
+    /// Checks if the Elements are still stored inline
+    #[cfg(test)]
+    pub fn is_inline(&self) -> bool {
+        matches!(self, Self::Inline { .. })
+    }
+
+    /// Appends the Element to the End of the Collection, moving
+    /// all Elements to the Heap if there is no inline Slot left
+    pub fn push(&mut self, item: T) {
+        match self {
+            Self::Inline { len, items } if *len < N => {
+                items[*len] = item;
+                *len += 1;
+            }
+            Self::Inline { items, .. } => {
+                let mut spilled = Vec::with_capacity(N * 2);
+                spilled.extend(items.iter_mut().map(std::mem::take));
+                spilled.push(item);
+                *self = Self::Heap(spilled);
+            }
+            Self::Heap(items) => items.push(item),
+        };
+    }
+
+    /// Removes the last Element and returns it
+    pub fn pop(&mut self) -> Option<T> {
+        match self {
+            Self::Inline { len, items } => {
+                if *len == 0 {
+                    return None;
+                }
+                *len -= 1;
+                Some(std::mem::take(&mut items[*len]))
+            }
+            Self::Heap(items) => items.pop(),
+        }
+    }
+
+    /// Removes the Element at the given Position and shifts all the
+    /// following Elements to the left
+    ///
+    /// ## Panics
+    /// If the Index is out of Bounds
+    pub fn remove(&mut self, index: usize) -> T {
+        match self {
+            Self::Inline { len, items } => {
+                assert!(index < *len, "index out of bounds");
+                items[index..*len].rotate_left(1);
+                *len -= 1;
+                std::mem::take(&mut items[*len])
+            }
+            Self::Heap(items) => items.remove(index),
+        }
+    }
+
+    /// Only keeps the Elements for which the Function returns true,
+    /// while keeping their Order
+    pub fn retain<F>(&mut self, mut func: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        match self {
+            Self::Inline { len, items } => {
+                let mut kept = 0;
+                for index in 0..*len {
+                    if func(&items[index]) {
+                        items.swap(kept, index);
+                        kept += 1;
+                    }
+                }
+                for item in items[kept..*len].iter_mut() {
+                    *item = T::default();
+                }
+                *len = kept;
+            }
+            Self::Heap(items) => items.retain(func),
+        };
+    }
+
+    /// Removes all the Elements, but keeps any allocated Memory
+    pub fn clear(&mut self) {
+        match self {
+            Self::Inline { len, items } => {
+                for item in items[..*len].iter_mut() {
+                    *item = T::default();
+                }
+                *len = 0;
+            }
+            Self::Heap(items) => items.clear(),
+        };
+    }
+}
+
+impl<T, const N: usize> Deref for InlineVec<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        match self {
+            Self::Inline { len, items } => &items[..*len],
+            Self::Heap(items) => items,
+        }
+    }
+}
+
+impl<T, const N: usize> DerefMut for InlineVec<T, N> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        match self {
+            Self::Inline { len, items } => &mut items[..*len],
+            Self::Heap(items) => items,
+        }
+    }
+}
+
+impl<T, const N: usize> Clone for InlineVec<T, N>
+where
+    T: Clone + Default,
+{
+    fn clone(&self) -> Self {
+        let mut result = Self::with_capacity(self.len());
+        for item in self.iter() {
+            result.push(item.clone());
+        }
+        result
+    }
+}
+
+impl<T, const N: usize> std::fmt::Debug for InlineVec<T, N>
+where
+    T: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T, const N: usize> PartialEq for InlineVec<T, N>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl<T, const N: usize> PartialEq<InlineVec<T, N>> for Vec<T>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &InlineVec<T, N>) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<T, const N: usize> IntoIterator for InlineVec<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<T, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        match self {
+            // Arrays only iterate by Value using the explicit Call in
+            // the 2018 Edition
+            Self::Inline { len, items } => {
+                IntoIter::Inline(IntoIterator::into_iter(items).take(len))
+            }
+            Self::Heap(items) => IntoIter::Heap(items.into_iter()),
+        }
+    }
+}
+
+/// An Iterator that moves the Elements out of the Collection, without
+/// copying the inline Elements to the Heap first
+#[derive(Debug)]
+pub(crate) enum IntoIter<T, const N: usize> {
+    Inline(Take<std::array::IntoIter<T, N>>),
+    Heap(std::vec::IntoIter<T>),
+}
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        match self {
+            Self::Inline(items) => items.next(),
+            Self::Heap(items) => items.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Self::Inline(items) => items.size_hint(),
+            Self::Heap(items) => items.size_hint(),
+        }
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for IntoIter<T, N> {
+    fn next_back(&mut self) -> Option<T> {
+        match self {
+            Self::Inline(items) => items.next_back(),
+            Self::Heap(items) => items.next_back(),
+        }
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for IntoIter<T, N> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_spills() {
+        let mut items: InlineVec<usize, 2> = InlineVec::new();
+        items.push(1);
+        items.push(2);
+        assert!(items.is_inline());
+
+        items.push(3);
+        assert!(!items.is_inline());
+        assert_eq!(&[1, 2, 3], &items[..]);
+        assert_eq!(Some(3), items.pop());
+    }
+
+    #[test]
+    fn with_capacity() {
+        assert!(InlineVec::<usize, 4>::with_capacity(4).is_inline());
+        assert!(!InlineVec::<usize, 4>::with_capacity(5).is_inline());
+    }
+
+    #[test]
+    fn remove_and_retain() {
+        let mut items: InlineVec<usize, 8> = InlineVec::new();
+        for i in 0..6 {
+            items.push(i);
+        }
+
+        assert_eq!(1, items.remove(1));
+        assert_eq!(&[0, 2, 3, 4, 5], &items[..]);
+
+        items.retain(|i| i % 2 == 0);
+        assert_eq!(&[0, 2, 4], &items[..]);
+        assert_eq!(vec![0, 2, 4], items);
+
+        items.clear();
+        assert_eq!(None, items.pop());
+        assert!(items.is_empty());
+    }
+
+    #[test]
+    fn into_iter() {
+        let mut items: InlineVec<String, 2> = InlineVec::new();
+        items.push("first".to_owned());
+        assert_eq!(
+            vec!["first".to_owned()],
+            items.clone().into_iter().collect::<Vec<_>>()
+        );
+
+        let mut inline = items.clone().into_iter();
+        assert_eq!(1, inline.len());
+        assert_eq!(Some("first".to_owned()), inline.next_back());
+        assert_eq!(None, inline.next());
+
+        items.push("second".to_owned());
+        items.push("third".to_owned());
+        assert_eq!(3, items.into_iter().count());
+    }
+}
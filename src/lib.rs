@@ -13,17 +13,31 @@ pub use status_code::StatusCode;
 mod method;
 pub use method::Method;
 
+mod version;
+pub use version::Version;
+
 /// Holds some more Types that are needed for Headers
 pub mod header;
 pub use header::Header;
 
+/// Holds the Types to parse and work with URIs
+pub mod uri;
+
 mod headers;
-pub use headers::Headers;
+pub use headers::{HeaderEntry, Headers, HeadersIntoIter};
+
+mod raw;
+pub use raw::{RawHead, RawHeader, Span};
+
+mod connection;
+pub use connection::KeepAlive;
 
 mod chunk;
-pub use chunk::Chunk;
+pub use chunk::{Chunk, ChunkExtension};
 
 pub(crate) mod general;
+pub(crate) mod grammar;
+pub(crate) mod inline_vec;
 
 /// This module holds all the Parsers that can deal
 /// with parsing the Data in multiple chunks and dont
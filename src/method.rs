@@ -1,6 +1,9 @@
+use crate::grammar;
+
 /// The different HTTP-Methods as defined by
 /// [RFC 2616 5.1.1](https://tools.ietf.org/html/rfc2616#section-5.1.1)
-#[derive(Debug, PartialEq, Clone)]
+/// and [RFC 5789](https://www.rfc-editor.org/rfc/rfc5789)
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub enum Method {
     /// Requests the Communication-Options available
     /// for a given Ressource
@@ -22,11 +25,17 @@ pub enum Method {
     TRACE,
     /// Reserved
     CONNECT,
+    /// Applies partial Modifications to a Ressource
+    PATCH,
+    /// Any other Method, like `PURGE` or `PROPFIND`, which
+    /// holds the Name of the Method
+    Extension(String),
 }
 
 impl Method {
-    /// Parses the raw Method into one of the known Methods,
-    /// returns None if the Method is unknown
+    /// Parses the raw Method into one of the known Methods or
+    /// an Extension-Method, returns None if the Method is not a
+    /// valid Token
     pub fn parse(raw_method: &str) -> Option<Method> {
         match raw_method {
             "OPTIONS" => Some(Method::OPTIONS),
@@ -37,13 +46,16 @@ impl Method {
             "DELETE" => Some(Method::DELETE),
             "TRACE" => Some(Method::TRACE),
             "CONNECT" => Some(Method::CONNECT),
+            "PATCH" => Some(Method::PATCH),
+            _ if grammar::is_token(raw_method.as_bytes()) => {
+                Some(Method::Extension(raw_method.to_owned()))
+            }
             _ => None,
         }
     }
 
-    /// Serializes the Method into a static String
-    /// for that Method
-    pub fn serialize(&self) -> &'static str {
+    /// Serializes the Method into the Name of the Method
+    pub fn serialize(&self) -> &str {
         match *self {
             Method::OPTIONS => "OPTIONS",
             Method::GET => "GET",
@@ -53,8 +65,42 @@ impl Method {
             Method::DELETE => "DELETE",
             Method::TRACE => "TRACE",
             Method::CONNECT => "CONNECT",
+            Method::PATCH => "PATCH",
+            Method::Extension(ref name) => name,
         }
     }
+
+    /// Checks if the Method is safe, meaning that it is essentially
+    /// read-only, as defined in
+    /// [RFC 9110 9.2.1](https://www.rfc-editor.org/rfc/rfc9110#section-9.2.1)
+    pub fn is_safe(&self) -> bool {
+        matches!(
+            self,
+            Method::GET | Method::HEAD | Method::OPTIONS | Method::TRACE
+        )
+    }
+
+    /// Checks if sending the same Request multiple times has the same
+    /// Effect as sending it once, as defined in
+    /// [RFC 9110 9.2.2](https://www.rfc-editor.org/rfc/rfc9110#section-9.2.2)
+    pub fn is_idempotent(&self) -> bool {
+        self.is_safe() || matches!(self, Method::PUT | Method::DELETE)
+    }
+
+    /// Checks if Responses to the Method may be cached, as defined in
+    /// [RFC 9110 9.2.3](https://www.rfc-editor.org/rfc/rfc9110#section-9.2.3)
+    pub fn is_cacheable(&self) -> bool {
+        matches!(self, Method::GET | Method::HEAD | Method::POST)
+    }
+
+    /// Checks if a Body in the Request has defined Semantics for the
+    /// Method, which is assumed for all Extension-Methods
+    pub fn allows_request_body(&self) -> bool {
+        matches!(
+            self,
+            Method::POST | Method::PUT | Method::PATCH | Method::Extension(_)
+        )
+    }
 }
 
 impl std::fmt::Display for Method {
@@ -66,6 +112,10 @@ impl std::fmt::Display for Method {
 #[cfg(feature = "wasm_serialize")]
 impl Method {
     /// Serializes the header into a WASM friendly form
+    ///
+    /// Extension-Methods are all serialized as `9`, so their Name has to
+    /// be transferred separately and can be restored using
+    /// `wasm_deserialize_with_name`
     pub fn wasm_serialize(&self) -> i32 {
         match *self {
             Self::OPTIONS => 0,
@@ -76,6 +126,8 @@ impl Method {
             Self::DELETE => 5,
             Self::TRACE => 6,
             Self::CONNECT => 7,
+            Self::PATCH => 8,
+            Self::Extension(_) => 9,
         }
     }
 
@@ -91,9 +143,23 @@ impl Method {
             5 => Some(Self::DELETE),
             6 => Some(Self::TRACE),
             7 => Some(Self::CONNECT),
+            8 => Some(Self::PATCH),
             _ => None,
         }
     }
+
+    /// Deserializes the Output from the `wasm_serialize` method
+    /// back into a valid Method, using the given Name for
+    /// Extension-Methods
+    pub fn wasm_deserialize_with_name(tmp: i32, name: &str) -> Option<Self> {
+        match tmp {
+            9 => match Self::parse(name)? {
+                Self::Extension(name) => Some(Self::Extension(name)),
+                _ => None,
+            },
+            _ => Self::wasm_deserialize(tmp),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -133,7 +199,55 @@ mod tests {
         assert_eq!(Some(Method::CONNECT), Method::parse("CONNECT"));
     }
     #[test]
+    fn parse_method_patch() {
+        assert_eq!(Some(Method::PATCH), Method::parse("PATCH"));
+    }
+    #[test]
+    fn parse_method_extension() {
+        assert_eq!(
+            Some(Method::Extension("PROPFIND".to_owned())),
+            Method::parse("PROPFIND")
+        );
+        assert_eq!("PURGE", Method::parse("PURGE").unwrap().serialize());
+    }
+    #[test]
     fn parse_method_invalid() {
-        assert_eq!(None, Method::parse("DIFFERENT"));
+        assert_eq!(None, Method::parse("DIFF(ERENT"));
+        assert_eq!(None, Method::parse(""));
+    }
+
+    #[test]
+    fn method_properties() {
+        assert!(Method::GET.is_safe() && Method::GET.is_idempotent());
+        assert!(!Method::PUT.is_safe() && Method::PUT.is_idempotent());
+        assert!(!Method::PATCH.is_idempotent());
+        assert!(Method::POST.is_cacheable() && !Method::PUT.is_cacheable());
+        assert!(Method::PATCH.allows_request_body() && !Method::GET.allows_request_body());
+
+        let extension = Method::Extension("PURGE".to_owned());
+        assert!(!extension.is_safe() && !extension.is_idempotent());
+        assert!(extension.allows_request_body());
+    }
+
+    #[cfg(feature = "wasm_serialize")]
+    #[test]
+    fn wasm_roundtrip() {
+        assert_eq!(
+            Some(Method::PATCH),
+            Method::wasm_deserialize(Method::PATCH.wasm_serialize())
+        );
+
+        let extension = Method::Extension("PURGE".to_owned());
+        assert_eq!(9, extension.wasm_serialize());
+        assert_eq!(None, Method::wasm_deserialize(9));
+        assert_eq!(
+            Some(extension),
+            Method::wasm_deserialize_with_name(9, "PURGE")
+        );
+        assert_eq!(None, Method::wasm_deserialize_with_name(9, "GET"));
+        assert_eq!(
+            Some(Method::GET),
+            Method::wasm_deserialize_with_name(1, "ignored")
+        );
     }
 }
@@ -0,0 +1,294 @@
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    hash::{Hash, Hasher},
+    ops::Range,
+};
+
+use crate::{header::HeaderCase, Headers};
+
+/// A Range of Bytes in the raw Head of a Message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Span {
+    /// The Position of the first Byte
+    pub start: usize,
+    /// The Position after the last Byte
+    pub end: usize,
+}
+
+impl Span {
+    /// Creates a new Span for the given Positions
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    /// Returns the Number of Bytes in the Span
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+    /// Checks if the Span does not contain any Bytes
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+    /// Returns the Span as a Range that can be used to index
+    /// into the Bytes of the Head
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+}
+
+impl From<(usize, usize)> for Span {
+    fn from((start, end): (usize, usize)) -> Self {
+        Self::new(start, end)
+    }
+}
+
+/// The Spans of a single Header-Line in the raw Head
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RawHeader {
+    /// The entire Line, including the Line-Ending and any
+    /// folded Continuation-Lines
+    pub line: Span,
+    /// The Field-Name
+    pub name: Span,
+    /// The Field-Value, without the surrounding Whitespace
+    pub value: Span,
+}
+
+/// The raw Head of a parsed Message, as it was received, together
+/// with the Spans of all its Elements
+///
+/// This is only kept by the Parsers if `ParserConfig::preserve_raw` is
+/// set. The Bytes are exactly the ones that were received, even if they
+/// contain obsolete Line-Folding, which is only unfolded in the parsed
+/// Values
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawHead<'a> {
+    bytes: Cow<'a, [u8]>,
+    start_line: Span,
+    start_line_parts: [Span; 3],
+    headers: Vec<RawHeader>,
+    end_of_head: Span,
+}
+
+impl<'a> RawHead<'a> {
+    /// Creates the raw Head from the Bytes the Parser received, the
+    /// Parts of the Start-Line and the Name and Value of every Header
+    pub(crate) fn new<I>(bytes: Cow<'a, [u8]>, start_line_parts: [Span; 3], headers: I) -> Self
+    where
+        I: IntoIterator<Item = (Span, Span)>,
+    {
+        // The Head always ends with an empty Line, which is either a CRLF
+        // or a bare LF in Lenient-Mode
+        let end = bytes.len();
+        let end_of_head = match bytes.ends_with(b"\r\n") {
+            true => Span::new(end - 2, end),
+            false => Span::new(end.saturating_sub(1), end),
+        };
+
+        // Every Line ends where the next one starts
+        let mut raw_headers: Vec<RawHeader> = Vec::new();
+        for (name, value) in headers {
+            if let Some(previous) = raw_headers.last_mut() {
+                previous.line.end = name.start;
+            }
+            raw_headers.push(RawHeader {
+                line: Span::new(name.start, end_of_head.start),
+                name,
+                value,
+            });
+        }
+
+        let start_line_end = raw_headers
+            .first()
+            .map(|header| header.line.start)
+            .unwrap_or(end_of_head.start);
+
+        Self {
+            bytes,
+            start_line: Span::new(start_line_parts[0].start, start_line_end),
+            start_line_parts,
+            headers: raw_headers,
+            end_of_head,
+        }
+    }
+
+    /// Returns all the Bytes of the Head, which may also include
+    /// empty Lines before the Start-Line
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+    /// Returns the Bytes in the given Span
+    ///
+    /// ## Panics
+    /// If the Span is not part of the Head
+    pub fn get(&self, span: Span) -> &[u8] {
+        &self.bytes[span.range()]
+    }
+
+    /// Returns the Span of the Start-Line, including its Line-Ending
+    pub fn start_line(&self) -> Span {
+        self.start_line
+    }
+    /// Returns the Spans of the three Parts of the Start-Line, which are
+    /// * Method, Request-Target and Version for Requests
+    /// * Version, Status-Code and Reason-Phrase for Responses
+    pub fn start_line_parts(&self) -> &[Span; 3] {
+        &self.start_line_parts
+    }
+    /// Returns the Spans of all the Header-Lines, in the Order
+    /// they were received
+    pub fn headers(&self) -> &[RawHeader] {
+        &self.headers
+    }
+    /// Returns the Span of the empty Line that ends the Head
+    pub fn end_of_head(&self) -> Span {
+        self.end_of_head
+    }
+
+    /// Serializes the given Headers into the Buffer, while re-using the
+    /// raw Line of every Header that is still the same as when it was
+    /// received, only the other Headers are serialized again using the
+    /// given Case
+    pub fn serialize_headers(&self, headers: &Headers<'_>, case: HeaderCase, buf: &mut Vec<u8>) {
+        // The unused raw Lines for every Name, in the Order they were received
+        let mut unused: HashMap<Name<'_>, Vec<usize>> = HashMap::with_capacity(self.headers.len());
+        for (position, raw) in self.headers.iter().enumerate() {
+            unused
+                .entry(Name(self.get(raw.name)))
+                .or_default()
+                .push(position);
+        }
+
+        for header in headers.iter() {
+            let value = match header.value.try_as_bytes() {
+                Some(value) => value,
+                None => {
+                    header.serialize_with_case(case, buf);
+                    continue;
+                }
+            };
+            let matches = |raw: &RawHeader| {
+                self.get(raw.value) == value
+                    && self
+                        .get(raw.name)
+                        .iter()
+                        .copied()
+                        .eq(header.key.cased(case))
+            };
+
+            let positions = unused.get_mut(&Name(header.key.as_ref().as_bytes()));
+            let position = positions.and_then(|positions| {
+                let index = positions
+                    .iter()
+                    .position(|position| matches(&self.headers[*position]))?;
+                Some(positions.remove(index))
+            });
+
+            match position {
+                Some(position) => buf.extend_from_slice(self.get(self.headers[position].line)),
+                None => header.serialize_with_case(case, buf),
+            };
+        }
+    }
+
+    /// Clones all the Bytes to produce a new and independant
+    /// raw Head
+    pub fn to_owned<'owned>(&self) -> RawHead<'owned> {
+        RawHead {
+            bytes: Cow::Owned(self.bytes.to_vec()),
+            start_line: self.start_line,
+            start_line_parts: self.start_line_parts,
+            headers: self.headers.clone(),
+            end_of_head: self.end_of_head,
+        }
+    }
+}
+
+/// The Name of a raw Header, which is hashed and compared without
+/// regard to the ASCII-Case like the Keys of the Headers
+#[derive(Debug, Clone, Copy)]
+struct Name<'a>(&'a [u8]);
+
+impl PartialEq for Name<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq_ignore_ascii_case(other.0)
+    }
+}
+impl Eq for Name<'_> {}
+
+impl Hash for Name<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for byte in self.0 {
+            state.write_u8(byte.to_ascii_lowercase());
+        }
+    }
+}
+
+/// Restores the original Bytes of every obsolete Line-Folding, given
+/// by its Position and Bytes, that was replaced in the Buffer while
+/// parsing
+pub(crate) fn restore_folds<'b>(buffer: &'b [u8], folds: &[(usize, Vec<u8>)]) -> Cow<'b, [u8]> {
+    if folds.is_empty() {
+        return Cow::Borrowed(buffer);
+    }
+
+    let mut bytes = buffer.to_vec();
+    for (start, original) in folds {
+        bytes[*start..*start + original.len()].copy_from_slice(original);
+    }
+    Cow::Owned(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn head() -> RawHead<'static> {
+        RawHead::new(
+            Cow::Borrowed(&b"GET / HTTP/1.1\r\nFirst:  one\r\nsecond: two\r\n\r\n"[..]),
+            [Span::new(0, 3), Span::new(4, 5), Span::new(6, 14)],
+            vec![
+                (Span::new(16, 21), Span::new(24, 27)),
+                (Span::new(29, 35), Span::new(37, 40)),
+            ],
+        )
+    }
+
+    #[test]
+    fn spans() {
+        let raw = head();
+
+        assert_eq!(Span::new(0, 16), raw.start_line());
+        assert_eq!(b"/", raw.get(raw.start_line_parts()[1]));
+        assert_eq!(b"First:  one\r\n", raw.get(raw.headers()[0].line));
+        assert_eq!(b"second: two\r\n", raw.get(raw.headers()[1].line));
+        assert_eq!(b"two", raw.get(raw.headers()[1].value));
+        assert_eq!(Span::new(42, 44), raw.end_of_head());
+    }
+
+    #[test]
+    fn serialize_headers() {
+        let raw = head();
+
+        let mut headers = Headers::new();
+        headers.append("second", "two");
+        headers.append("First", "changed");
+        headers.append("First", "one");
+        headers.append("Third", 3);
+
+        let mut result = Vec::new();
+        raw.serialize_headers(&headers, HeaderCase::Preserve, &mut result);
+        assert_eq!(
+            "second: two\r\nFirst: changed\r\nFirst:  one\r\nThird: 3\r\n".as_bytes(),
+            &result
+        );
+
+        let mut result = Vec::new();
+        raw.serialize_headers(&headers, HeaderCase::Title, &mut result);
+        assert_eq!(
+            "Second: two\r\nFirst: changed\r\nFirst:  one\r\nThird: 3\r\n".as_bytes(),
+            &result
+        );
+    }
+}
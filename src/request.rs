@@ -1,4 +1,16 @@
-use crate::{general::StringContainer, header::HeaderValue, Headers, Method};
+use crate::{
+    connection,
+    general::StringContainer,
+    grammar,
+    header::{self, HeaderCase},
+    streaming_parser::{ParseError, ParseResult},
+    uri::{
+        default_port, normalize_path, percent_decode, Host, PathError, Query, RequestTarget,
+        TargetForm,
+    },
+    Headers, KeepAlive, Method, RawHead, Version,
+};
+use std::borrow::Cow;
 
 #[derive(Debug)]
 enum BodyData<'a> {
@@ -25,67 +37,211 @@ impl<'a> PartialEq for BodyData<'a> {
 #[derive(Debug, PartialEq)]
 pub struct Request<'a> {
     method: Method,
-    path: StringContainer<'a>,
-    protocol: &'a str,
+    /// The parsed Request-Target, or the raw Path together with the
+    /// Reason it is not a valid Request-Target
+    target: Result<RequestTarget<'a>, (StringContainer<'a>, ParseError)>,
+    version: Version,
     headers: Headers<'a>,
     body: BodyData<'a>,
+    raw: Option<RawHead<'a>>,
 }
 
 impl<'a> Request<'a> {
     /// Creates a new Request with the given Data as its
     /// initial Data
     pub fn new(
-        protocol: &'a str,
+        version: Version,
         method: Method,
         path: &'a str,
         headers: Headers<'a>,
         body: &'a [u8],
     ) -> Self {
+        let target = RequestTarget::parse_container(StringContainer::Ref(path), &method);
         Self {
             method,
-            path: StringContainer::Ref(path),
-            protocol,
+            target,
+            version,
             headers,
             body: BodyData::Ref(body),
+            raw: None,
         }
     }
 
+    /// Creates a new Request with a Request-Target that was
+    /// already parsed
+    pub(crate) fn with_target(
+        version: Version,
+        method: Method,
+        target: RequestTarget<'a>,
+        headers: Headers<'a>,
+        body: &'a [u8],
+    ) -> Self {
+        Self {
+            method,
+            target: Ok(target),
+            version,
+            headers,
+            body: BodyData::Ref(body),
+            raw: None,
+        }
+    }
+
+    /// Attaches the raw Head the Request was parsed from
+    pub(crate) fn with_raw_head(mut self, raw: RawHead<'a>) -> Self {
+        self.raw = Some(raw);
+        self
+    }
+
     /// Serializes the Request and returns the final Data
     /// as a tuple of (HTTP-Head, HTTP-Body)
+    ///
+    /// If the Request still has its raw Head, the Request-Line and all the
+    /// Headers that were not modified are re-emitted exactly as received
     pub fn serialize(&self) -> (Vec<u8>, &[u8]) {
-        let method = self.method.serialize();
-        let path = self.path.as_ref();
-        let capacity = method.len() + 1 + path.len() + 1 + self.protocol.len() + 4;
-        let mut result = Vec::with_capacity(capacity);
+        self.serialize_with_case(HeaderCase::Preserve)
+    }
+
+    /// Serializes the Request like [`serialize`](Self::serialize), while
+    /// writing the Names of all the Headers in the given Case
+    pub fn serialize_with_case(&self, case: HeaderCase) -> (Vec<u8>, &[u8]) {
+        let raw = match &self.raw {
+            Some(raw) => raw,
+            None => {
+                let method = self.method.serialize();
+                let path = self.path();
+                let capacity = method.len() + 1 + path.len() + 1 + 8 + 4;
+                let mut result = Vec::with_capacity(capacity);
 
-        // The first line with method, path, protocol
-        result.extend_from_slice(method.as_bytes());
-        result.push(b' ');
-        result.extend_from_slice(path.as_bytes());
-        result.push(b' ');
-        result.extend_from_slice(self.protocol.as_bytes());
-        result.extend_from_slice("\r\n".as_bytes());
+                // The first line with method, path, version
+                self.serialize_request_line(&mut result);
 
-        // The headers
-        self.headers.serialize(&mut result);
+                // The headers
+                self.headers.serialize_with_case(case, &mut result);
 
-        // The ending of the head
-        result.extend_from_slice("\r\n".as_bytes());
+                // The ending of the head
+                result.extend_from_slice("\r\n".as_bytes());
+
+                return (result, self.body.as_ref());
+            }
+        };
+
+        let mut result = Vec::with_capacity(raw.bytes().len());
+
+        let [method, path, version] = raw.start_line_parts().map(|span| raw.get(span));
+        if method == self.method.serialize().as_bytes()
+            && path == self.path().as_bytes()
+            && Version::parse_bytes(version) == Some(self.version)
+        {
+            result.extend_from_slice(raw.get(raw.start_line()));
+        } else {
+            self.serialize_request_line(&mut result);
+        }
+        raw.serialize_headers(&self.headers, case, &mut result);
+        result.extend_from_slice(raw.get(raw.end_of_head()));
 
         (result, self.body.as_ref())
     }
 
-    /// Returns the Protocol of the Request
-    pub fn protocol(&self) -> &'a str {
-        &self.protocol
+    /// Serializes the Request like [`serialize`](Self::serialize), but
+    /// refuses to do so if the Method, the Request-Target or any of the
+    /// Headers is invalid, which could otherwise be used to inject Data
+    /// into the serialized Request
+    pub fn try_serialize(&self) -> ParseResult<(Vec<u8>, &[u8])> {
+        if !grammar::is_token(self.method.serialize().as_bytes()) {
+            return Err(ParseError::InvalidMethod);
+        }
+        self.target()?;
+        self.headers.validate()?;
+
+        Ok(self.serialize())
+    }
+
+    fn serialize_request_line(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(self.method.serialize().as_bytes());
+        buf.push(b' ');
+        buf.extend_from_slice(self.path().as_bytes());
+        buf.push(b' ');
+        self.version.serialize(buf);
+        buf.extend_from_slice("\r\n".as_bytes());
+    }
+
+    /// Returns the HTTP-Version of the Request
+    pub fn version(&self) -> Version {
+        self.version
     }
     /// Returns the Method of the Request
     pub fn method(&self) -> &Method {
         &self.method
     }
     /// Returns the Path of the Request
-    pub fn path(&'a self) -> &'a str {
-        self.path.as_ref()
+    pub fn path(&self) -> &str {
+        match &self.target {
+            Ok(target) => target.as_str(),
+            Err((raw, _)) => raw.as_ref(),
+        }
+    }
+    /// Returns the Path of the Request parsed as a Request-Target
+    pub fn target(&self) -> ParseResult<&RequestTarget<'a>> {
+        self.target.as_ref().map_err(|(_, e)| e.clone())
+    }
+    /// Returns the Host the Request is targeted at, which checks that
+    /// * there is exactly one `Host` Header
+    /// * the `Host` Header matches the Authority of the Request-Target,
+    ///   if it is in the absolute-form or authority-form
+    ///
+    /// HTTP/1.0 Requests may omit the `Host` Header, if the Request-Target
+    /// contains an Authority
+    pub fn host(&self) -> ParseResult<Host<'_>> {
+        let mut values = self.headers.get_all(header::HOST);
+        let header = match (values.next(), values.next()) {
+            (_, Some(_)) => return Err(ParseError::DuplicateHost),
+            (Some(value), None) => {
+                let raw = value.try_as_str_ref().ok_or(ParseError::InvalidHost)?;
+                Some(Host::parse(raw)?)
+            }
+            (None, None) => None,
+        };
+
+        let target = self.target()?;
+        let authority = match target.form() {
+            TargetForm::Absolute | TargetForm::Authority => {
+                target.authority().map(Host::parse).transpose()?
+            }
+            _ => None,
+        };
+
+        match (header, authority) {
+            (Some(header), Some(authority)) => {
+                let port = target.scheme().and_then(default_port);
+                if !authority.matches(&header, port) {
+                    return Err(ParseError::HostMismatch);
+                }
+                Ok(authority)
+            }
+            (Some(header), None) => Ok(header),
+            (None, Some(authority)) if self.version < Version::Http11 => Ok(authority),
+            _ => Err(ParseError::MissingHost),
+        }
+    }
+    /// Returns the percent-decoded Path of the Request, without
+    /// the Query
+    pub fn decoded_path(&self) -> ParseResult<Cow<'_, str>> {
+        percent_decode(self.target()?.path())
+    }
+    /// Returns the normalised Path of the Request, without the Query,
+    /// see [`normalize_path`](crate::uri::normalize_path) for the
+    /// Details
+    pub fn normalized_path(&self) -> Result<String, PathError> {
+        let target = self.target().map_err(|_| PathError::InvalidTarget)?;
+        normalize_path(target.path())
+    }
+    /// Returns the decoded Parameters of the Query, which are empty
+    /// if the Request has no Query or an invalid Request-Target
+    pub fn query(&self) -> Query<'_> {
+        match self.target().ok().and_then(RequestTarget::query) {
+            Some(raw_query) => Query::parse(raw_query),
+            None => Query::new(),
+        }
     }
     /// Returns the Headers of the Request
     pub fn headers(&self) -> &Headers<'a> {
@@ -99,14 +255,35 @@ impl<'a> Request<'a> {
     pub fn body(&self) -> &[u8] {
         self.body.as_ref()
     }
+    /// Returns the raw Head the Request was parsed from, which is only
+    /// kept if `ParserConfig::preserve_raw` was set
+    pub fn raw_head(&self) -> Option<&RawHead<'a>> {
+        self.raw.as_ref()
+    }
 
     /// Checks if the Requests expects a
     /// Keep-alive connection
     pub fn is_keep_alive(&self) -> bool {
-        match self.headers.get("Connection") {
-            None => false,
-            Some(value) => value.eq_ignore_case(&HeaderValue::StrRef("Keep-Alive")),
-        }
+        !self.must_close()
+    }
+
+    /// Checks if the Connection has to be closed after this
+    /// Request, based on the HTTP-Version and the `Connection` Header
+    pub fn must_close(&self) -> bool {
+        connection::must_close(self.version, &self.headers)
+    }
+    /// Returns all the Tokens listed in the `Connection` Headers
+    pub fn connection_tokens(&self) -> Vec<&str> {
+        connection::tokens(&self.headers)
+    }
+    /// Returns the Parameters of the `Keep-Alive` Header, if present
+    pub fn keep_alive(&self) -> Option<KeepAlive> {
+        connection::keep_alive(&self.headers)
+    }
+    /// Returns the Names of the Hop-by-Hop Headers that are
+    /// listed in the `Connection` Headers
+    pub fn hop_by_hop_headers(&self) -> Vec<&str> {
+        connection::hop_by_hop_headers(&self.headers)
     }
 
     /// Overwrites the Path with the new Path
@@ -114,12 +291,25 @@ impl<'a> Request<'a> {
     where
         'b: 'a,
     {
-        self.path = StringContainer::Ref(n_path);
+        self.target = RequestTarget::parse_container(StringContainer::Ref(n_path), &self.method);
     }
     /// Overwrites the Path with the new Path, but using
     /// an owned String instead of a reference
     pub fn set_path_owned(&mut self, n_path: String) {
-        self.path = StringContainer::Owned(n_path);
+        self.target = RequestTarget::parse_container(StringContainer::Owned(n_path), &self.method);
+    }
+
+    /// Replaces the Query of the Path with the given Query, which
+    /// removes the Query if it is empty
+    pub fn set_query(&mut self, query: &Query<'_>) {
+        let base = self.path().split('?').next().unwrap_or("");
+
+        let mut n_path = base.to_owned();
+        if !query.is_empty() {
+            n_path.push('?');
+            n_path.push_str(&query.serialize());
+        }
+        self.set_path_owned(n_path);
     }
 
     /// Replaces the current Body with the given Data
@@ -130,7 +320,7 @@ impl<'a> Request<'a> {
 
 impl std::fmt::Display for Request<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "[{}] Path: '{}'", self.method, self.path.as_ref())
+        write!(f, "[{}] Path: '{}'", self.method, self.path())
     }
 }
 
@@ -140,7 +330,13 @@ mod tests {
 
     #[test]
     fn update_path_to_own() {
-        let mut req = Request::new("HTTP/1.1", Method::GET, "/test/path", Headers::new(), &[]);
+        let mut req = Request::new(
+            Version::Http11,
+            Method::GET,
+            "/test/path",
+            Headers::new(),
+            &[],
+        );
 
         let path = req.path().to_owned();
         req.set_path_ref(&path[1..]);
@@ -153,7 +349,13 @@ mod tests {
         let mut headers = Headers::new();
         headers.set("test-1", "value-1");
 
-        let req = Request::new("HTTP/1.1", Method::GET, "/test", headers, "body".as_bytes());
+        let req = Request::new(
+            Version::Http11,
+            Method::GET,
+            "/test",
+            headers,
+            "body".as_bytes(),
+        );
         let raw_header = "GET /test HTTP/1.1\r\ntest-1: value-1\r\n\r\n";
         let header_resp = raw_header.as_bytes().to_vec();
         let body_resp = "body".as_bytes();
@@ -161,11 +363,84 @@ mod tests {
         assert_eq!(req.serialize(), (header_resp, body_resp));
     }
     #[test]
+    fn serialize_with_case() {
+        let mut headers = Headers::new();
+        headers.set("x-custom-key", "Value");
+        headers.set(header::CONTENT_TYPE, "text/plain");
+
+        let req = Request::new(Version::Http11, Method::GET, "/", headers, "".as_bytes());
+        assert_eq!(
+            "GET / HTTP/1.1\r\nX-Custom-Key: Value\r\nContent-Type: text/plain\r\n\r\n".as_bytes(),
+            &req.serialize_with_case(HeaderCase::Title).0
+        );
+        assert_eq!(
+            "GET / HTTP/1.1\r\nx-custom-key: Value\r\ncontent-type: text/plain\r\n\r\n".as_bytes(),
+            &req.serialize_with_case(HeaderCase::Lower).0
+        );
+    }
+    #[test]
+    fn try_serialize() {
+        let mut req = Request::new(
+            Version::Http11,
+            Method::GET,
+            "/test",
+            Headers::new(),
+            "".as_bytes(),
+        );
+        req.header_mut().set("Host", "example.com");
+        assert_eq!(
+            Ok((
+                "GET /test HTTP/1.1\r\nHost: example.com\r\n\r\n"
+                    .as_bytes()
+                    .to_vec(),
+                "".as_bytes()
+            )),
+            req.try_serialize()
+        );
+
+        req.header_mut()
+            .set("X-Forwarded-For", "1.2.3.4\r\nX-Admin: true");
+        assert_eq!(Err(ParseError::InvalidHeaderValue), req.try_serialize());
+
+        req.header_mut().remove("X-Forwarded-For");
+        req.set_path_owned("/test HTTP/1.1\r\nX-Admin: true\r\n\r\nGET /".to_owned());
+        assert_eq!(Err(ParseError::InvalidTarget), req.try_serialize());
+    }
+    #[test]
+    fn try_serialize_method_injection() {
+        let req = Request::new(
+            Version::Http11,
+            Method::Extension("GET / HTTP/1.1\r\nX-Admin: true".to_owned()),
+            "/test",
+            Headers::new(),
+            "".as_bytes(),
+        );
+        assert_eq!(Err(ParseError::InvalidMethod), req.try_serialize());
+
+        let req = Request::new(
+            Version::Http11,
+            Method::Extension("PURGE".to_owned()),
+            "/test",
+            Headers::new(),
+            "".as_bytes(),
+        );
+        assert_eq!(
+            "PURGE /test HTTP/1.1\r\n\r\n".as_bytes(),
+            &req.try_serialize().unwrap().0
+        );
+    }
+    #[test]
     fn serialize_valid_no_body() {
         let mut headers = Headers::new();
         headers.set("test-1", "value-1");
 
-        let req = Request::new("HTTP/1.1", Method::GET, "/test", headers, "".as_bytes());
+        let req = Request::new(
+            Version::Http11,
+            Method::GET,
+            "/test",
+            headers,
+            "".as_bytes(),
+        );
         let raw_header = "GET /test HTTP/1.1\r\ntest-1: value-1\r\n\r\n";
         let resp_header = raw_header.as_bytes().to_vec();
         let resp_body = "".as_bytes();
@@ -178,26 +453,260 @@ mod tests {
         let mut headers = Headers::new();
         headers.set("test-1", "value-1");
 
-        let req = Request::new("HTTP/1.1", Method::GET, "/test", headers, "".as_bytes());
+        let req = Request::new(
+            Version::Http11,
+            Method::GET,
+            "/test",
+            headers,
+            "".as_bytes(),
+        );
+
+        // HTTP/1.1 Connections are persistent by default
+        assert!(req.is_keep_alive());
+    }
+    #[test]
+    fn is_keep_alive_not_set_http10() {
+        let req = Request::new(Version::Http10, Method::GET, "/test", Headers::new(), &[]);
+
+        assert!(!req.is_keep_alive());
+    }
+    #[test]
+    fn is_keep_alive_is_set_http10() {
+        let mut headers = Headers::new();
+        headers.set("Connection", "keep-alive, Upgrade");
+
+        let req = Request::new(Version::Http10, Method::GET, "/test", headers, &[]);
 
-        assert_eq!(false, req.is_keep_alive());
+        assert!(req.is_keep_alive());
+        assert_eq!(vec!["keep-alive", "Upgrade"], req.connection_tokens());
+        assert_eq!(vec!["keep-alive", "Upgrade"], req.hop_by_hop_headers());
     }
     #[test]
     fn is_keep_alive_is_set() {
         let mut headers = Headers::new();
         headers.set("Connection", "Keep-Alive");
 
-        let req = Request::new("HTTP/1.1", Method::GET, "/test", headers, "".as_bytes());
+        let req = Request::new(
+            Version::Http11,
+            Method::GET,
+            "/test",
+            headers,
+            "".as_bytes(),
+        );
 
-        assert_eq!(true, req.is_keep_alive());
+        assert!(req.is_keep_alive());
     }
     #[test]
     fn is_keep_alive_is_set_to_off() {
         let mut headers = Headers::new();
         headers.set("Connection", "Close");
 
-        let req = Request::new("HTTP/1.1", Method::GET, "/test", headers, "".as_bytes());
+        let req = Request::new(
+            Version::Http11,
+            Method::GET,
+            "/test",
+            headers,
+            "".as_bytes(),
+        );
+
+        assert!(!req.is_keep_alive());
+    }
+    #[test]
+    fn keep_alive_params() {
+        let mut headers = Headers::new();
+        headers.set("Keep-Alive", "timeout=5, max=100");
+
+        let req = Request::new(Version::Http11, Method::GET, "/test", headers, &[]);
+
+        assert_eq!(
+            Some(KeepAlive {
+                timeout: Some(5),
+                max: Some(100)
+            }),
+            req.keep_alive()
+        );
+    }
+
+    #[test]
+    fn target_absolute() {
+        let req = Request::new(
+            Version::Http11,
+            Method::GET,
+            "http://example.com/test?a=b",
+            Headers::new(),
+            &[],
+        );
+
+        let target = req.target().unwrap();
+        assert_eq!(Some("http"), target.scheme());
+        assert_eq!(Some("example.com"), target.authority());
+        assert_eq!("/test", target.path());
+        assert_eq!(Some("a=b"), target.query());
+    }
+    #[test]
+    fn target_follows_path() {
+        let mut req = Request::new(Version::Http11, Method::GET, "/test", Headers::new(), &[]);
+        assert_eq!("/test", req.target().unwrap().path());
+
+        req.set_path_owned("/other?a=b".to_owned());
+        assert_eq!("/other", req.target().unwrap().path());
+        assert_eq!(Some("a=b"), req.target().unwrap().query());
+
+        req.set_query(&Query::new());
+        assert_eq!("/other", req.target().unwrap().as_str());
+
+        req.set_path_ref("invalid");
+        assert_eq!(Err(ParseError::InvalidTarget), req.target());
+        assert_eq!("invalid", req.path());
+
+        req.set_path_owned("with space".to_owned());
+        assert_eq!(Err(ParseError::InvalidTarget), req.target());
+        assert_eq!("with space", req.path());
+        assert_eq!(
+            "GET with space HTTP/1.1\r\n\r\n".as_bytes(),
+            &req.serialize().0
+        );
+    }
+
+    #[test]
+    fn decoded_path_and_query() {
+        let req = Request::new(
+            Version::Http11,
+            Method::GET,
+            "/some%20path/?name=J%C3%BCrgen&tag=a&tag=b+c",
+            Headers::new(),
+            &[],
+        );
+
+        assert_eq!(Ok("/some path/".into()), req.decoded_path());
+        let query = req.query();
+        assert_eq!(Some("Jürgen"), query.get("name"));
+        assert_eq!(vec!["a", "b c"], query.get_all("tag").collect::<Vec<_>>());
 
-        assert_eq!(false, req.is_keep_alive());
+        let req = Request::new(
+            Version::Http11,
+            Method::GET,
+            "http://example.com?name=value",
+            Headers::new(),
+            &[],
+        );
+        assert_eq!(Some("value"), req.query().get("name"));
+
+        let req = Request::new(
+            Version::Http11,
+            Method::CONNECT,
+            "/path?name=value",
+            Headers::new(),
+            &[],
+        );
+        assert_eq!(None, req.query().get("name"));
+    }
+    #[test]
+    fn set_query() {
+        let mut req = Request::new(
+            Version::Http11,
+            Method::GET,
+            "/test?a=1",
+            Headers::new(),
+            &[],
+        );
+
+        let mut query = req.query().into_owned();
+        query.set("b", "two words");
+        req.set_query(&query);
+        assert_eq!("/test?a=1&b=two%20words", req.path());
+
+        req.set_query(&Query::new());
+        assert_eq!("/test", req.path());
+    }
+
+    #[test]
+    fn normalized_path() {
+        let req = Request::new(
+            Version::Http11,
+            Method::GET,
+            "/static//css/../%69mg/a.png?v=1",
+            Headers::new(),
+            &[],
+        );
+        assert_eq!(Ok("/static/img/a.png".to_owned()), req.normalized_path());
+
+        let req = Request::new(
+            Version::Http11,
+            Method::GET,
+            "/static/%2e%2e/x",
+            Headers::new(),
+            &[],
+        );
+        assert_eq!(Err(PathError::EncodedDotSegment), req.normalized_path());
+
+        let req = Request::new(Version::Http11, Method::OPTIONS, "*", Headers::new(), &[]);
+        assert_eq!(Err(PathError::NotAbsolute), req.normalized_path());
+    }
+
+    #[test]
+    fn host() {
+        let mut headers = Headers::new();
+        headers.set("Host", "example.com:8080");
+        let req = Request::new(Version::Http11, Method::GET, "/", headers, &[]);
+        assert_eq!("example.com:8080", req.host().unwrap().to_string());
+
+        let mut headers = Headers::new();
+        headers.set("Host", "Example.com");
+        let req = Request::new(
+            Version::Http11,
+            Method::GET,
+            "http://example.com:80/",
+            headers,
+            &[],
+        );
+        assert_eq!("example.com:80", req.host().unwrap().to_string());
+
+        let req = Request::new(
+            Version::Http10,
+            Method::GET,
+            "http://example.com/",
+            Headers::new(),
+            &[],
+        );
+        assert_eq!("example.com", req.host().unwrap().to_string());
+    }
+    #[test]
+    fn host_invalid() {
+        let req = Request::new(Version::Http11, Method::GET, "/", Headers::new(), &[]);
+        assert_eq!(Err(ParseError::MissingHost), req.host().map(|_| ()));
+
+        let mut headers = Headers::new();
+        headers.append("Host", "example.com");
+        headers.append("Host", "example.com");
+        let req = Request::new(Version::Http11, Method::GET, "/", headers, &[]);
+        assert_eq!(Err(ParseError::DuplicateHost), req.host().map(|_| ()));
+
+        let mut headers = Headers::new();
+        headers.set("Host", "example.org");
+        let req = Request::new(
+            Version::Http11,
+            Method::GET,
+            "http://example.com/",
+            headers,
+            &[],
+        );
+        assert_eq!(Err(ParseError::HostMismatch), req.host().map(|_| ()));
+
+        let mut headers = Headers::new();
+        headers.set("Host", "example.com:443");
+        let req = Request::new(
+            Version::Http11,
+            Method::CONNECT,
+            "example.com:8443",
+            headers,
+            &[],
+        );
+        assert_eq!(Err(ParseError::HostMismatch), req.host().map(|_| ()));
+
+        let mut headers = Headers::new();
+        headers.set("Host", "exa mple");
+        let req = Request::new(Version::Http11, Method::GET, "/", headers, &[]);
+        assert_eq!(Err(ParseError::InvalidHost), req.host().map(|_| ()));
     }
 }
@@ -1,98 +1,117 @@
 use crate::{
-    header::{HeaderKey, HeaderValue},
-    Headers, StatusCode,
+    connection,
+    header::{self, HeaderCase, HeaderError, HeaderKey, HeaderValue},
+    streaming_parser::{framing, ParseResult},
+    Headers, KeepAlive, RawHead, Span, StatusCode, Version,
 };
 
-#[derive(Debug)]
-enum ProtocolData<'a> {
-    Ref(&'a str),
-    Owned(String),
-}
-
-impl<'a> AsRef<str> for ProtocolData<'a> {
-    fn as_ref(&self) -> &str {
-        match self {
-            Self::Ref(tmp) => tmp,
-            Self::Owned(tmp) => &tmp,
-        }
-    }
-}
-
-impl<'a> PartialEq for ProtocolData<'a> {
-    fn eq(&self, other: &ProtocolData<'_>) -> bool {
-        self.as_ref().eq(other.as_ref())
-    }
-}
-
 /// Represents a single HTTP-Request
 #[derive(Debug, PartialEq)]
 pub struct Response<'a> {
     status_code: StatusCode,
-    protocol: ProtocolData<'a>,
+    version: Version,
     headers: Headers<'a>,
     body: Vec<u8>,
+    raw: Option<RawHead<'a>>,
 }
 
 impl<'a> Response<'a> {
     /// Creates a new Response with the given
     /// Data as its inital State
     pub fn new(
-        protocol: &'a str,
+        version: Version,
         status_code: StatusCode,
         headers: Headers<'a>,
         body: Vec<u8>,
     ) -> Self {
         Self {
             status_code,
-            protocol: ProtocolData::Ref(protocol),
+            version,
             headers,
             body,
+            raw: None,
         }
     }
 
-    /// Creates a new Response that owns all of
-    /// its Data
-    pub(crate) fn new_owned(
-        protocol: String,
-        status_code: StatusCode,
-        headers: Headers<'a>,
-        body: Vec<u8>,
-    ) -> Self {
-        Self {
-            status_code,
-            protocol: ProtocolData::Owned(protocol),
-            headers,
-            body,
-        }
+    /// Attaches the raw Head the Response was parsed from
+    pub(crate) fn with_raw_head(mut self, raw: RawHead<'a>) -> Self {
+        self.raw = Some(raw);
+        self
     }
 
     /// Serialzes the Response and returns the Data as
     /// a tuple of form (HTTP-Head, HTTP-Body)
+    ///
+    /// If the Response still has its raw Head, the Status-Line and all the
+    /// Headers that were not modified are re-emitted exactly as received
     pub fn serialize(&self) -> (Vec<u8>, &[u8]) {
-        let protocol = self.protocol.as_ref();
-        let status_code = self.status_code.serialize();
+        self.serialize_with_case(HeaderCase::Preserve)
+    }
 
-        let capacity = protocol.len() + 1 + status_code.len() + 4;
-        let mut result = Vec::with_capacity(capacity);
+    /// Serializes the Response like [`serialize`](Self::serialize), while
+    /// writing the Names of all the Headers in the given Case
+    pub fn serialize_with_case(&self, case: HeaderCase) -> (Vec<u8>, &[u8]) {
+        let raw = match &self.raw {
+            Some(raw) => raw,
+            None => {
+                let capacity = 8 + 1 + 4 + self.status_code.reason().len() + 4;
+                let mut result = Vec::with_capacity(capacity);
+
+                // The first line with version, status-code
+                self.serialize_status_line(&mut result);
+
+                // The headers
+                self.headers.serialize_with_case(case, &mut result);
+
+                // The ending of the head
+                result.extend_from_slice("\r\n".as_bytes());
+
+                return (result, &self.body);
+            }
+        };
+
+        let mut result = Vec::with_capacity(raw.bytes().len());
+
+        let [version, code, reason] = *raw.start_line_parts();
+        let status = std::str::from_utf8(raw.get(Span::new(code.start, reason.end)))
+            .ok()
+            .and_then(StatusCode::parse);
+        let unchanged = match status {
+            Some(status) => {
+                status == self.status_code && status.reason() == self.status_code.reason()
+            }
+            None => false,
+        };
+        if unchanged && Version::parse_bytes(raw.get(version)) == Some(self.version) {
+            result.extend_from_slice(raw.get(raw.start_line()));
+        } else {
+            self.serialize_status_line(&mut result);
+        }
+        raw.serialize_headers(&self.headers, case, &mut result);
+        result.extend_from_slice(raw.get(raw.end_of_head()));
 
-        // The first line with method, path, protocol
-        result.extend_from_slice(protocol.as_bytes());
-        result.push(b' ');
-        result.extend_from_slice(status_code.as_bytes());
-        result.extend_from_slice("\r\n".as_bytes());
+        (result, &self.body)
+    }
 
-        // The headers
-        self.headers.serialize(&mut result);
+    /// Serializes the Response like [`serialize`](Self::serialize), but
+    /// refuses to do so if any of the Headers is invalid, which could
+    /// otherwise be used to inject Data into the serialized Response
+    pub fn try_serialize(&self) -> ParseResult<(Vec<u8>, &[u8])> {
+        self.headers.validate()?;
 
-        // The ending of the head
-        result.extend_from_slice("\r\n".as_bytes());
+        Ok(self.serialize())
+    }
 
-        (result, &self.body)
+    fn serialize_status_line(&self, buf: &mut Vec<u8>) {
+        self.version.serialize(buf);
+        buf.push(b' ');
+        self.status_code.serialize(buf);
+        buf.extend_from_slice("\r\n".as_bytes());
     }
 
-    /// Returns the Protocol of the Response
-    pub fn protocol(&self) -> &str {
-        self.protocol.as_ref()
+    /// Returns the HTTP-Version of the Response
+    pub fn version(&self) -> Version {
+        self.version
     }
     /// Returns the StatusCode of the Response
     pub fn status_code(&self) -> &StatusCode {
@@ -106,6 +125,11 @@ impl<'a> Response<'a> {
     pub fn body(&self) -> &[u8] {
         &self.body
     }
+    /// Returns the raw Head the Response was parsed from, which is only
+    /// kept if `ParserConfig::preserve_raw` was set
+    pub fn raw_head(&self) -> Option<&RawHead<'a>> {
+        self.raw.as_ref()
+    }
 
     /// Adds the Key-Value Pair as a new Header to
     /// the Response or replaces the old Value of the
@@ -119,6 +143,17 @@ impl<'a> Response<'a> {
         self.headers.set(key, value);
     }
 
+    /// Adds the Key-Value Pair like [`add_header`](Self::add_header),
+    /// after checking that the Header is valid, see
+    /// [`Headers::try_set`](crate::Headers::try_set)
+    pub fn try_add_header<K, V>(&mut self, key: K, value: V) -> Result<(), HeaderError>
+    where
+        K: Into<HeaderKey<'a>>,
+        V: Into<HeaderValue<'a>>,
+    {
+        self.headers.try_set(key, value)
+    }
+
     /// Replaces the old Body of the Response with the
     /// new given Body and updates the Content-Length
     /// Header as well with the new Length
@@ -130,27 +165,59 @@ impl<'a> Response<'a> {
     /// Checks if the Response is send using
     /// `Transfer-Encoding: Chunked`
     pub fn is_chunked(&self) -> bool {
-        match self.headers.get("Transfer-Encoding") {
+        match self.headers.get(header::TRANSFER_ENCODING) {
+            Some(value) => match value.try_as_str_ref() {
+                Some(encoding) => framing::is_chunked(encoding),
+                None => false,
+            },
             None => false,
-            Some(value) => value.eq_ignore_case(&HeaderValue::StrRef("Chunked")),
         }
     }
 
+    /// Checks if the Connection has to be closed after this
+    /// Response, based on the HTTP-Version and the `Connection` Header,
+    /// or because the Body is only delimited by closing the Connection
+    pub fn must_close(&self) -> bool {
+        connection::must_close(self.version, &self.headers)
+            || framing::reads_until_close(
+                self.status_code.as_u16(),
+                self.headers
+                    .get(header::TRANSFER_ENCODING)
+                    .and_then(|value| value.try_as_bytes()),
+                self.headers.get(header::CONTENT_LENGTH).is_some(),
+            )
+    }
+    /// Returns all the Tokens listed in the `Connection` Headers
+    pub fn connection_tokens(&self) -> Vec<&str> {
+        connection::tokens(&self.headers)
+    }
+    /// Returns the Parameters of the `Keep-Alive` Header, if present
+    pub fn keep_alive(&self) -> Option<KeepAlive> {
+        connection::keep_alive(&self.headers)
+    }
+    /// Returns the Names of the Hop-by-Hop Headers that are
+    /// listed in the `Connection` Headers
+    pub fn hop_by_hop_headers(&self) -> Vec<&str> {
+        connection::hop_by_hop_headers(&self.headers)
+    }
+
     /// Clones the entire Response to produce a new indepandent
     /// Response
-    pub fn to_owned<'refed, 'owned>(&'refed self) -> Response<'owned> {
-        Response::new_owned(
-            self.protocol.as_ref().to_owned(),
-            self.status_code.clone(),
-            self.headers.to_owned(),
-            self.body.clone(),
-        )
+    pub fn to_owned<'owned>(&self) -> Response<'owned> {
+        Response {
+            status_code: self.status_code.clone(),
+            version: self.version,
+            headers: self.headers.to_owned(),
+            body: self.body.clone(),
+            raw: self.raw.as_ref().map(RawHead::to_owned),
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::streaming_parser::ParseError;
 
     #[test]
     fn serialize_valid() {
@@ -158,7 +225,7 @@ mod tests {
         headers.set("test-1", "value-1");
 
         let req = Response::new(
-            "HTTP/1.1",
+            Version::Http11,
             StatusCode::OK,
             headers,
             "body".as_bytes().to_vec(),
@@ -170,12 +237,66 @@ mod tests {
         assert_eq!(req.serialize(), (resp_header, resp_body));
     }
 
+    #[test]
+    fn try_serialize() {
+        let mut resp = Response::new(
+            Version::Http11,
+            StatusCode::Found,
+            Headers::new(),
+            Vec::new(),
+        );
+        assert_eq!(
+            Err(HeaderError::InvalidValue),
+            resp.try_add_header("Location", "/login\r\nSet-Cookie: session=stolen")
+        );
+        assert_eq!(Ok(()), resp.try_add_header("Location", "/login"));
+        assert_eq!(
+            Ok((
+                "HTTP/1.1 302 Found\r\nLocation: /login\r\n\r\n"
+                    .as_bytes()
+                    .to_vec(),
+                "".as_bytes()
+            )),
+            resp.try_serialize()
+        );
+
+        resp.add_header("Location", "/login\r\n\r\n<html>");
+        assert_eq!(Err(ParseError::InvalidHeaderValue), resp.try_serialize());
+    }
+
+    #[test]
+    fn serialize_with_case() {
+        let mut headers = Headers::new();
+        headers.set("content-length", "4");
+        headers.set("X-CUSTOM", "Value");
+
+        let resp = Response::new(
+            Version::Http10,
+            StatusCode::OK,
+            headers,
+            "body".as_bytes().to_vec(),
+        );
+        assert_eq!(
+            "HTTP/1.0 200 OK\r\nContent-Length: 4\r\nX-Custom: Value\r\n\r\n".as_bytes(),
+            &resp.serialize_with_case(HeaderCase::Title).0
+        );
+        assert_eq!(
+            "HTTP/1.0 200 OK\r\ncontent-length: 4\r\nX-CUSTOM: Value\r\n\r\n".as_bytes(),
+            &resp.serialize_with_case(HeaderCase::Preserve).0
+        );
+    }
+
     #[test]
     fn serialize_valid_no_body() {
         let mut headers = Headers::new();
         headers.set("test-1", "value-1");
 
-        let req = Response::new("HTTP/1.1", StatusCode::OK, headers, "".as_bytes().to_vec());
+        let req = Response::new(
+            Version::Http11,
+            StatusCode::OK,
+            headers,
+            "".as_bytes().to_vec(),
+        );
         let raw_resp_header = "HTTP/1.1 200 OK\r\ntest-1: value-1\r\n\r\n";
         let resp_header = raw_resp_header.as_bytes().to_vec();
         let resp_body = "".as_bytes();
@@ -188,32 +309,61 @@ mod tests {
         let mut headers = Headers::new();
         headers.set("test-1", "value-1");
 
-        let resp = Response::new("HTTP/1.1", StatusCode::OK, headers, "".as_bytes().to_vec());
+        let resp = Response::new(
+            Version::Http11,
+            StatusCode::OK,
+            headers,
+            "".as_bytes().to_vec(),
+        );
 
-        assert_eq!(false, resp.is_chunked());
+        assert!(!resp.is_chunked());
     }
     #[test]
     fn is_chunked_set() {
         let mut headers = Headers::new();
         headers.set("Transfer-Encoding", "Chunked");
 
-        let resp = Response::new("HTTP/1.1", StatusCode::OK, headers, "".as_bytes().to_vec());
+        let resp = Response::new(
+            Version::Http11,
+            StatusCode::OK,
+            headers,
+            "".as_bytes().to_vec(),
+        );
 
-        assert_eq!(true, resp.is_chunked());
+        assert!(resp.is_chunked());
+    }
+    #[test]
+    fn is_chunked_set_in_list() {
+        let mut headers = Headers::new();
+        headers.set("Transfer-Encoding", "gzip, chunked");
+
+        let resp = Response::new(
+            Version::Http11,
+            StatusCode::OK,
+            headers,
+            "".as_bytes().to_vec(),
+        );
+
+        assert!(resp.is_chunked());
     }
     #[test]
     fn is_chunked_set_differently() {
         let mut headers = Headers::new();
         headers.set("Transfer-Encoding", "compress");
 
-        let resp = Response::new("HTTP/1.1", StatusCode::OK, headers, "".as_bytes().to_vec());
+        let resp = Response::new(
+            Version::Http11,
+            StatusCode::OK,
+            headers,
+            "".as_bytes().to_vec(),
+        );
 
-        assert_eq!(false, resp.is_chunked());
+        assert!(!resp.is_chunked());
     }
 
     #[test]
     fn to_owned() {
-        let resp = Response::new("HTTP/1.1", StatusCode::OK, Headers::new(), Vec::new());
+        let resp = Response::new(Version::Http11, StatusCode::OK, Headers::new(), Vec::new());
 
         let cloned = resp.to_owned();
 
@@ -221,4 +371,44 @@ mod tests {
 
         assert_eq!(&StatusCode::OK, cloned.status_code())
     }
+
+    #[test]
+    fn must_close() {
+        let mut headers = Headers::new();
+        headers.set("Content-Length", 0);
+        let resp = Response::new(Version::Http11, StatusCode::OK, headers, Vec::new());
+        assert!(!resp.must_close());
+
+        let mut headers = Headers::new();
+        headers.set("Connection", "close");
+        let resp = Response::new(Version::Http11, StatusCode::OK, headers, Vec::new());
+        assert!(resp.must_close());
+
+        let resp = Response::new(Version::Http10, StatusCode::OK, Headers::new(), Vec::new());
+        assert!(resp.must_close());
+    }
+    #[test]
+    fn must_close_without_length() {
+        let resp = Response::new(Version::Http11, StatusCode::OK, Headers::new(), Vec::new());
+        assert!(resp.must_close());
+
+        let mut headers = Headers::new();
+        headers.set("Transfer-Encoding", "gzip");
+        headers.set("Content-Length", 4);
+        let resp = Response::new(Version::Http11, StatusCode::OK, headers, Vec::new());
+        assert!(resp.must_close());
+
+        let mut headers = Headers::new();
+        headers.set("Transfer-Encoding", "gzip, chunked");
+        let resp = Response::new(Version::Http11, StatusCode::OK, headers, Vec::new());
+        assert!(!resp.must_close());
+
+        let resp = Response::new(
+            Version::Http11,
+            StatusCode::NoContent,
+            Headers::new(),
+            Vec::new(),
+        );
+        assert!(!resp.must_close());
+    }
 }
@@ -1,208 +1,383 @@
-/// Represents all the known and defined StatusCodes
-#[derive(Debug, PartialEq, Clone)]
-pub enum StatusCode {
+use std::convert::TryFrom;
+
+use crate::{grammar, streaming_parser::ParseError};
+
+/// A Status-Code of a Response, which can hold any three-digit Code
+/// and an optional custom Reason-Phrase, as defined in
+/// [RFC 9110 15](https://www.rfc-editor.org/rfc/rfc9110#section-15)
+///
+/// Two StatusCodes are equal if their Codes are equal, regardless
+/// of their Reason-Phrases. Because of this, the Constants can not be
+/// used as Patterns in a `match`, which should use
+/// [`as_u16`](Self::as_u16) instead
+#[derive(Debug, Clone)]
+pub struct StatusCode {
+    code: u16,
+    reason: Option<String>,
+}
+
+// The Constants keep the Names of the previous Enum-Variants
+#[allow(non_upper_case_globals)]
+impl StatusCode {
     /// The Request should be continued by the Client
-    Continue,
+    pub const Continue: StatusCode = StatusCode::known(100);
     /// The Server acknowledges and accepts the Request to
     /// switch to another Protocol
-    SwitchingProtocols,
+    pub const SwitchingProtocols: StatusCode = StatusCode::known(101);
+    /// The Server has accepted the Request but has not
+    /// completed it yet
+    pub const Processing: StatusCode = StatusCode::known(102);
+    /// Preliminary Headers that are send before the final Response
+    pub const EarlyHints: StatusCode = StatusCode::known(103);
     /// The Request has successfully been processed
-    OK,
+    pub const OK: StatusCode = StatusCode::known(200);
     /// The Request successfully created a new Ressource
-    Created,
+    pub const Created: StatusCode = StatusCode::known(201);
     /// The Request was successfully accpeted to be processed
     /// but has not been completed yet
-    Accepted,
+    pub const Accepted: StatusCode = StatusCode::known(202);
     /// The returned Metainformation was not returned by the
     /// Origin-Server
-    NonAuthoritativeInformation,
+    pub const NonAuthoritativeInformation: StatusCode = StatusCode::known(203);
     /// The Request was successful but there is no Data returned
-    NoContent,
+    pub const NoContent: StatusCode = StatusCode::known(204);
     /// The Request has been successfully fulfilled and the
     /// Client can clear its input Content
-    ResetContent,
+    pub const ResetContent: StatusCode = StatusCode::known(205);
     /// The requested partial Data has been fulfilled
-    PartialContent,
+    pub const PartialContent: StatusCode = StatusCode::known(206);
+    /// The Response contains the Status of multiple Operations
+    pub const MultiStatus: StatusCode = StatusCode::known(207);
+    /// The Members of a Binding have already been enumerated
+    pub const AlreadyReported: StatusCode = StatusCode::known(208);
+    /// The Response is the Result of Instance-Manipulations
+    pub const IMUsed: StatusCode = StatusCode::known(226);
     /// The requested Ressource corresponds multiple Ressources
-    MultipleChoices,
+    pub const MultipleChoices: StatusCode = StatusCode::known(300);
     /// The Requested Data was moved to another URI
-    MovedPermanently,
+    pub const MovedPermanently: StatusCode = StatusCode::known(301);
     /// The requested Ressource temporarily resides under a
     /// different URI
-    Found,
+    pub const Found: StatusCode = StatusCode::known(302);
     /// The Response to this Request can be found at a
     /// different URI
-    SeeOther,
+    pub const SeeOther: StatusCode = StatusCode::known(303);
     /// The requested Ressource was not modified between the
     /// last Request and now
-    NotModified,
+    pub const NotModified: StatusCode = StatusCode::known(304);
     /// The Ressource can only be accessed through a Proxy
-    UseProxy,
+    pub const UseProxy: StatusCode = StatusCode::known(305);
     /// The requested Ressource temporarily resides under a
     /// different URI
-    TemporaryRedirect,
+    pub const TemporaryRedirect: StatusCode = StatusCode::known(307);
+    /// The requested Ressource permanently resides under a
+    /// different URI and the Method must not change
+    pub const PermanentRedirect: StatusCode = StatusCode::known(308);
     /// The Request was not properly send or received
-    BadRequest,
+    pub const BadRequest: StatusCode = StatusCode::known(400);
     /// The Request tried to access something it is not
     /// authorized to do
-    Unauthorized,
+    pub const Unauthorized: StatusCode = StatusCode::known(401);
     /// Reserved for future use
-    PaymentRequired,
+    pub const PaymentRequired: StatusCode = StatusCode::known(402);
     /// The requested Ressource is not allowed to be accessed
-    Forbidden,
+    pub const Forbidden: StatusCode = StatusCode::known(403);
     /// The requested Ressource could not be found
-    NotFound,
+    pub const NotFound: StatusCode = StatusCode::known(404);
     /// The requested Method is not allowed for the specified
     /// Ressource
-    MethodNotAllowed,
+    pub const MethodNotAllowed: StatusCode = StatusCode::known(405);
     /// The Ressource is not capable of accepting the Request
-    NotAcceptable,
+    pub const NotAcceptable: StatusCode = StatusCode::known(406);
     /// The Client should first Authenticate with a Proxy and
     /// before attempting the Request again
-    ProxyAuthenticationRequired,
+    pub const ProxyAuthenticationRequired: StatusCode = StatusCode::known(407);
     /// The Server decided that the Client took to long and the
     /// Request timed out
-    RequestTimeOut,
+    pub const RequestTimeOut: StatusCode = StatusCode::known(408);
     /// Request could not complete because there was a conflict
     /// current State of the Ressource
-    Conflict,
+    pub const Conflict: StatusCode = StatusCode::known(409);
     /// The Ressource is no longer available
-    Gone,
+    pub const Gone: StatusCode = StatusCode::known(410);
     /// The Server only accepts Requests where the Content-Length
     /// is set
-    LengthRequired,
+    pub const LengthRequired: StatusCode = StatusCode::known(411);
     /// The given Precondition failed
-    PreconditionFailed,
+    pub const PreconditionFailed: StatusCode = StatusCode::known(412);
     /// The Request-Entity was larger than what the Server allows
-    RequestEntityTooLarge,
+    pub const RequestEntityTooLarge: StatusCode = StatusCode::known(413);
     /// The URI is longer than what the Server allows
-    RequestURITooLarge,
+    pub const RequestURITooLarge: StatusCode = StatusCode::known(414);
     /// The Media-Type is not supported by the Server for this ressource
-    UnsupportedMediaType,
+    pub const UnsupportedMediaType: StatusCode = StatusCode::known(415);
     /// The Requested Range could not be satisfied by the Server
-    RequestedRangeNotSatisfiable,
+    pub const RequestedRangeNotSatisfiable: StatusCode = StatusCode::known(416);
     /// The given Expectation has failed
-    ExpectationFailed,
+    pub const ExpectationFailed: StatusCode = StatusCode::known(417);
     /// An April Fool's Status-Code that some servers use for a
     /// variety of Situations
-    ImATeapot,
+    pub const ImATeapot: StatusCode = StatusCode::known(418);
+    /// The Request was directed at a Server that is not able
+    /// to produce a Response
+    pub const MisdirectedRequest: StatusCode = StatusCode::known(421);
+    /// The Content of the Request could not be processed
+    pub const UnprocessableContent: StatusCode = StatusCode::known(422);
+    /// The requested Ressource is locked
+    pub const Locked: StatusCode = StatusCode::known(423);
+    /// The Request failed because a previous Request failed
+    pub const FailedDependency: StatusCode = StatusCode::known(424);
+    /// The Server is unwilling to process a Request that
+    /// might be replayed
+    pub const TooEarly: StatusCode = StatusCode::known(425);
+    /// The Client should switch to a different Protocol
+    pub const UpgradeRequired: StatusCode = StatusCode::known(426);
+    /// The Server requires the Request to be conditional
+    pub const PreconditionRequired: StatusCode = StatusCode::known(428);
+    /// The Client has send too many Requests in a given
+    /// amount of Time
+    pub const TooManyRequests: StatusCode = StatusCode::known(429);
+    /// The Headers of the Request are larger than what the
+    /// Server allows
+    pub const RequestHeaderFieldsTooLarge: StatusCode = StatusCode::known(431);
+    /// The requested Ressource can not be provided because
+    /// of legal Reasons
+    pub const UnavailableForLegalReasons: StatusCode = StatusCode::known(451);
     /// The Server Processing encountered some internal Problem
     /// and could not process the Request
-    InternalServerError,
+    pub const InternalServerError: StatusCode = StatusCode::known(500);
     /// Some requested Functionality is not implemented on the Server
-    NotImplemented,
+    pub const NotImplemented: StatusCode = StatusCode::known(501);
     /// An Error occured at a Gateway while sending the
     /// Request to the Target-Server
-    BadGateway,
+    pub const BadGateway: StatusCode = StatusCode::known(502);
     /// The requested Service is currently unavailable
-    ServiceUnavailable,
+    pub const ServiceUnavailable: StatusCode = StatusCode::known(503);
     /// The Gateway did not received a Response in time
-    GatewayTimeout,
+    pub const GatewayTimeout: StatusCode = StatusCode::known(504);
     /// The requested HTTP-Version is not supported by the Server
-    HTTPVersionNotSupported,
+    pub const HTTPVersionNotSupported: StatusCode = StatusCode::known(505);
+    /// The Server has an internal Configuration Error during
+    /// Content-Negotiation
+    pub const VariantAlsoNegotiates: StatusCode = StatusCode::known(506);
+    /// The Server is unable to store the Data needed to
+    /// complete the Request
+    pub const InsufficientStorage: StatusCode = StatusCode::known(507);
+    /// The Server detected an infinite Loop while processing
+    /// the Request
+    pub const LoopDetected: StatusCode = StatusCode::known(508);
+    /// Further Extensions to the Request are required
+    pub const NotExtended: StatusCode = StatusCode::known(510);
+    /// The Client needs to authenticate to gain Network Access
+    pub const NetworkAuthenticationRequired: StatusCode = StatusCode::known(511);
 }
 
 impl StatusCode {
-    /// Parses the Raw Response Status-Code to the enum
+    const fn known(code: u16) -> Self {
+        Self { code, reason: None }
+    }
+
+    /// Creates the StatusCode for the given Code, returns None if
+    /// the Code is not in the Range of 100 to 999
+    pub fn from_u16(code: u16) -> Option<Self> {
+        if (100..1000).contains(&code) {
+            Some(Self::known(code))
+        } else {
+            None
+        }
+    }
+
+    /// Sets a custom Reason-Phrase that is used instead of the
+    /// default one, returns None if the Reason-Phrase contains
+    /// invalid Characters
+    pub fn with_reason<R>(mut self, reason: R) -> Option<Self>
+    where
+        R: Into<String>,
+    {
+        let reason = reason.into();
+        if !grammar::is_field_value(reason.as_bytes()) {
+            return None;
+        }
+
+        self.reason = Some(reason);
+        Some(self)
+    }
+
+    /// Parses the Raw Response Status-Code, optionally followed by
+    /// a space and the Reason-Phrase, like `200 OK`
     pub fn parse(raw: &str) -> Option<Self> {
-        if raw.len() < 3 {
+        let bytes = raw.as_bytes();
+        if bytes.len() < 3 || !bytes[..3].iter().all(|b| b.is_ascii_digit()) {
             return None;
         }
 
-        let key = &raw[0..3];
-
-        match key {
-            "100" => Some(StatusCode::Continue),
-            "101" => Some(StatusCode::SwitchingProtocols),
-            "200" => Some(StatusCode::OK),
-            "201" => Some(StatusCode::Created),
-            "202" => Some(StatusCode::Accepted),
-            "203" => Some(StatusCode::NonAuthoritativeInformation),
-            "204" => Some(StatusCode::NoContent),
-            "205" => Some(StatusCode::ResetContent),
-            "206" => Some(StatusCode::PartialContent),
-            "300" => Some(StatusCode::MultipleChoices),
-            "301" => Some(StatusCode::MovedPermanently),
-            "302" => Some(StatusCode::Found),
-            "303" => Some(StatusCode::SeeOther),
-            "304" => Some(StatusCode::NotModified),
-            "305" => Some(StatusCode::UseProxy),
-            "307" => Some(StatusCode::TemporaryRedirect),
-            "400" => Some(StatusCode::BadRequest),
-            "401" => Some(StatusCode::Unauthorized),
-            "402" => Some(StatusCode::PaymentRequired),
-            "403" => Some(StatusCode::Forbidden),
-            "404" => Some(StatusCode::NotFound),
-            "405" => Some(StatusCode::MethodNotAllowed),
-            "406" => Some(StatusCode::NotAcceptable),
-            "407" => Some(StatusCode::ProxyAuthenticationRequired),
-            "408" => Some(StatusCode::RequestTimeOut),
-            "409" => Some(StatusCode::Conflict),
-            "410" => Some(StatusCode::Gone),
-            "411" => Some(StatusCode::LengthRequired),
-            "412" => Some(StatusCode::PreconditionFailed),
-            "413" => Some(StatusCode::RequestEntityTooLarge),
-            "414" => Some(StatusCode::RequestURITooLarge),
-            "415" => Some(StatusCode::UnsupportedMediaType),
-            "416" => Some(StatusCode::RequestedRangeNotSatisfiable),
-            "417" => Some(StatusCode::ExpectationFailed),
-            "418" => Some(StatusCode::ImATeapot),
-            "500" => Some(StatusCode::InternalServerError),
-            "501" => Some(StatusCode::NotImplemented),
-            "502" => Some(StatusCode::BadGateway),
-            "503" => Some(StatusCode::ServiceUnavailable),
-            "504" => Some(StatusCode::GatewayTimeout),
-            "505" => Some(StatusCode::HTTPVersionNotSupported),
-            _ => None,
+        let code = bytes[..3]
+            .iter()
+            .fold(0, |acc, b| acc * 10 + u16::from(b - b'0'));
+        let status = Self::from_u16(code)?;
+
+        let reason = match bytes.get(3) {
+            None => return Some(status),
+            Some(b' ') => &raw[4..],
+            Some(_) => return None,
+        };
+        if reason.is_empty() || Some(reason) == status.canonical_reason() {
+            return Some(status);
+        }
+        status.with_reason(reason)
+    }
+
+    /// Returns the numeric Code
+    pub fn as_u16(&self) -> u16 {
+        self.code
+    }
+
+    /// Returns the Reason-Phrase, which is either the custom one or
+    /// the default one for the Code
+    pub fn reason(&self) -> &str {
+        match &self.reason {
+            Some(reason) => reason,
+            None => self.canonical_reason().unwrap_or(""),
         }
     }
 
-    /// Serialzes the given StatusCode
-    pub fn serialize(&self) -> &'static str {
-        match *self {
-            Self::Continue => "100 Continue",
-            Self::SwitchingProtocols => "101 Switching Protocols",
-            Self::OK => "200 OK",
-            Self::Created => "201 Created",
-            Self::Accepted => "202 Accepted",
-            Self::NonAuthoritativeInformation => "203 Non-Authoritative Information",
-            Self::NoContent => "204 No Content",
-            Self::ResetContent => "205 Reset Content",
-            Self::PartialContent => "206 Partial Content",
-            Self::MultipleChoices => "300 Multiple Choices",
-            Self::MovedPermanently => "301 Moved Permanently",
-            Self::Found => "302 Found",
-            Self::SeeOther => "303 See Other",
-            Self::NotModified => "304 Not Modified",
-            Self::UseProxy => "305 Use Proxy",
-            Self::TemporaryRedirect => "307 Temporary Redirect",
-            Self::BadRequest => "400 Bad Request",
-            Self::Unauthorized => "401 Unauthorized",
-            Self::PaymentRequired => "402 Payment Required",
-            Self::Forbidden => "403 Forbidden",
-            Self::NotFound => "404 Not Found",
-            Self::MethodNotAllowed => "405 Method Not Allowed",
-            Self::NotAcceptable => "406 Not Acceptable",
-            Self::ProxyAuthenticationRequired => "407 Proxy Authentication Required",
-            Self::RequestTimeOut => "408 Request Time-out",
-            Self::Conflict => "409 Conflict",
-            Self::Gone => "410 Gone",
-            Self::LengthRequired => "411 Length Required",
-            Self::PreconditionFailed => "412 Precondition Failed",
-            Self::RequestEntityTooLarge => "413 Request Entity Too Large",
-            Self::RequestURITooLarge => "414 Request-URI Too Large",
-            Self::UnsupportedMediaType => "415 Unsupported Media Type",
-            Self::RequestedRangeNotSatisfiable => "416 Requested Range Not Satisfiable",
-            Self::ExpectationFailed => "417 Expectation Failed",
-            Self::ImATeapot => "418 I'm a Teapot",
-            Self::InternalServerError => "500 Internal Server Error",
-            Self::NotImplemented => "501 Not Implemented",
-            Self::BadGateway => "502 Bad Gateway",
-            Self::ServiceUnavailable => "503 Service Unavailable",
-            Self::GatewayTimeout => "504 Gateway Time-out",
-            Self::HTTPVersionNotSupported => "505 HTTP Version Not Supported",
+    /// Returns the default Reason-Phrase for the Code, if it is
+    /// registered with the IANA
+    pub fn canonical_reason(&self) -> Option<&'static str> {
+        match self.code {
+            100 => Some("Continue"),
+            101 => Some("Switching Protocols"),
+            102 => Some("Processing"),
+            103 => Some("Early Hints"),
+            200 => Some("OK"),
+            201 => Some("Created"),
+            202 => Some("Accepted"),
+            203 => Some("Non-Authoritative Information"),
+            204 => Some("No Content"),
+            205 => Some("Reset Content"),
+            206 => Some("Partial Content"),
+            207 => Some("Multi-Status"),
+            208 => Some("Already Reported"),
+            226 => Some("IM Used"),
+            300 => Some("Multiple Choices"),
+            301 => Some("Moved Permanently"),
+            302 => Some("Found"),
+            303 => Some("See Other"),
+            304 => Some("Not Modified"),
+            305 => Some("Use Proxy"),
+            307 => Some("Temporary Redirect"),
+            308 => Some("Permanent Redirect"),
+            400 => Some("Bad Request"),
+            401 => Some("Unauthorized"),
+            402 => Some("Payment Required"),
+            403 => Some("Forbidden"),
+            404 => Some("Not Found"),
+            405 => Some("Method Not Allowed"),
+            406 => Some("Not Acceptable"),
+            407 => Some("Proxy Authentication Required"),
+            408 => Some("Request Timeout"),
+            409 => Some("Conflict"),
+            410 => Some("Gone"),
+            411 => Some("Length Required"),
+            412 => Some("Precondition Failed"),
+            413 => Some("Content Too Large"),
+            414 => Some("URI Too Long"),
+            415 => Some("Unsupported Media Type"),
+            416 => Some("Range Not Satisfiable"),
+            417 => Some("Expectation Failed"),
+            418 => Some("I'm a Teapot"),
+            421 => Some("Misdirected Request"),
+            422 => Some("Unprocessable Content"),
+            423 => Some("Locked"),
+            424 => Some("Failed Dependency"),
+            425 => Some("Too Early"),
+            426 => Some("Upgrade Required"),
+            428 => Some("Precondition Required"),
+            429 => Some("Too Many Requests"),
+            431 => Some("Request Header Fields Too Large"),
+            451 => Some("Unavailable For Legal Reasons"),
+            500 => Some("Internal Server Error"),
+            501 => Some("Not Implemented"),
+            502 => Some("Bad Gateway"),
+            503 => Some("Service Unavailable"),
+            504 => Some("Gateway Timeout"),
+            505 => Some("HTTP Version Not Supported"),
+            506 => Some("Variant Also Negotiates"),
+            507 => Some("Insufficient Storage"),
+            508 => Some("Loop Detected"),
+            510 => Some("Not Extended"),
+            511 => Some("Network Authentication Required"),
+            _ => None,
         }
     }
+
+    /// Checks if the StatusCode is in the 1xx Class
+    pub fn is_informational(&self) -> bool {
+        (100..200).contains(&self.code)
+    }
+    /// Checks if the StatusCode is in the 2xx Class
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.code)
+    }
+    /// Checks if the StatusCode is in the 3xx Class
+    pub fn is_redirection(&self) -> bool {
+        (300..400).contains(&self.code)
+    }
+    /// Checks if the StatusCode is in the 4xx Class
+    pub fn is_client_error(&self) -> bool {
+        (400..500).contains(&self.code)
+    }
+    /// Checks if the StatusCode is in the 5xx Class
+    pub fn is_server_error(&self) -> bool {
+        (500..600).contains(&self.code)
+    }
+
+    /// Serializes the StatusCode, including its Reason-Phrase, into
+    /// the given Buffer
+    pub fn serialize(&self, buf: &mut Vec<u8>) {
+        // The Code always has exactly three Digits
+        let code = self.code;
+        buf.extend_from_slice(&[
+            b'0' + (code / 100) as u8,
+            b'0' + (code / 10 % 10) as u8,
+            b'0' + (code % 10) as u8,
+            b' ',
+        ]);
+        buf.extend_from_slice(self.reason().as_bytes());
+    }
+}
+
+impl PartialEq for StatusCode {
+    fn eq(&self, other: &Self) -> bool {
+        self.code == other.code
+    }
+}
+impl Eq for StatusCode {}
+
+impl std::hash::Hash for StatusCode {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.code.hash(state);
+    }
+}
+
+impl TryFrom<u16> for StatusCode {
+    type Error = ParseError;
+
+    fn try_from(code: u16) -> Result<Self, Self::Error> {
+        Self::from_u16(code).ok_or(ParseError::InvalidStatusCode)
+    }
+}
+
+impl From<StatusCode> for u16 {
+    fn from(status: StatusCode) -> Self {
+        status.code
+    }
+}
+
+impl std::fmt::Display for StatusCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.code, self.reason())
+    }
 }
 
 #[cfg(feature = "wasm_serialize")]
@@ -210,99 +385,17 @@ impl StatusCode {
     /// Deserializes the i32 Value to a StatusCode for easier
     /// exchange between WASM and the Host
     pub fn wasm_deserialize(key: i32) -> Option<Self> {
-        match key {
-            100 => Some(StatusCode::Continue),
-            101 => Some(StatusCode::SwitchingProtocols),
-            200 => Some(StatusCode::OK),
-            201 => Some(StatusCode::Created),
-            202 => Some(StatusCode::Accepted),
-            203 => Some(StatusCode::NonAuthoritativeInformation),
-            204 => Some(StatusCode::NoContent),
-            205 => Some(StatusCode::ResetContent),
-            206 => Some(StatusCode::PartialContent),
-            300 => Some(StatusCode::MultipleChoices),
-            301 => Some(StatusCode::MovedPermanently),
-            302 => Some(StatusCode::Found),
-            303 => Some(StatusCode::SeeOther),
-            304 => Some(StatusCode::NotModified),
-            305 => Some(StatusCode::UseProxy),
-            307 => Some(StatusCode::TemporaryRedirect),
-            400 => Some(StatusCode::BadRequest),
-            401 => Some(StatusCode::Unauthorized),
-            402 => Some(StatusCode::PaymentRequired),
-            403 => Some(StatusCode::Forbidden),
-            404 => Some(StatusCode::NotFound),
-            405 => Some(StatusCode::MethodNotAllowed),
-            406 => Some(StatusCode::NotAcceptable),
-            407 => Some(StatusCode::ProxyAuthenticationRequired),
-            408 => Some(StatusCode::RequestTimeOut),
-            409 => Some(StatusCode::Conflict),
-            410 => Some(StatusCode::Gone),
-            411 => Some(StatusCode::LengthRequired),
-            412 => Some(StatusCode::PreconditionFailed),
-            413 => Some(StatusCode::RequestEntityTooLarge),
-            414 => Some(StatusCode::RequestURITooLarge),
-            415 => Some(StatusCode::UnsupportedMediaType),
-            416 => Some(StatusCode::RequestedRangeNotSatisfiable),
-            417 => Some(StatusCode::ExpectationFailed),
-            418 => Some(StatusCode::ImATeapot),
-            500 => Some(StatusCode::InternalServerError),
-            501 => Some(StatusCode::NotImplemented),
-            502 => Some(StatusCode::BadGateway),
-            503 => Some(StatusCode::ServiceUnavailable),
-            504 => Some(StatusCode::GatewayTimeout),
-            505 => Some(StatusCode::HTTPVersionNotSupported),
-            _ => None,
-        }
+        u16::try_from(key).ok().and_then(Self::from_u16)
     }
 
     /// Serializes the given StatusCode to a simple
     /// i32 Value, which makes it easier to exchange between
     /// a WASM module and its host
+    ///
+    /// A custom Reason-Phrase is not part of the Value and has to be
+    /// transferred separately
     pub fn wasm_serialize(&self) -> i32 {
-        match *self {
-            Self::Continue => 100,
-            Self::SwitchingProtocols => 101,
-            Self::OK => 200,
-            Self::Created => 201,
-            Self::Accepted => 202,
-            Self::NonAuthoritativeInformation => 203,
-            Self::NoContent => 204,
-            Self::ResetContent => 205,
-            Self::PartialContent => 206,
-            Self::MultipleChoices => 300,
-            Self::MovedPermanently => 301,
-            Self::Found => 302,
-            Self::SeeOther => 303,
-            Self::NotModified => 304,
-            Self::UseProxy => 305,
-            Self::TemporaryRedirect => 307,
-            Self::BadRequest => 400,
-            Self::Unauthorized => 401,
-            Self::PaymentRequired => 402,
-            Self::Forbidden => 403,
-            Self::NotFound => 404,
-            Self::MethodNotAllowed => 405,
-            Self::NotAcceptable => 406,
-            Self::ProxyAuthenticationRequired => 407,
-            Self::RequestTimeOut => 408,
-            Self::Conflict => 409,
-            Self::Gone => 410,
-            Self::LengthRequired => 411,
-            Self::PreconditionFailed => 412,
-            Self::RequestEntityTooLarge => 413,
-            Self::RequestURITooLarge => 414,
-            Self::UnsupportedMediaType => 415,
-            Self::RequestedRangeNotSatisfiable => 416,
-            Self::ExpectationFailed => 417,
-            Self::ImATeapot => 418,
-            Self::InternalServerError => 500,
-            Self::NotImplemented => 501,
-            Self::BadGateway => 502,
-            Self::ServiceUnavailable => 503,
-            Self::GatewayTimeout => 504,
-            Self::HTTPVersionNotSupported => 505,
-        }
+        i32::from(self.code)
     }
 }
 
@@ -313,7 +406,107 @@ mod tests {
     #[test]
     fn parse_invalid() {
         assert_eq!(None, StatusCode::parse("1"));
-        assert_eq!(None, StatusCode::parse("123"));
+        assert_eq!(None, StatusCode::parse("099"));
+        assert_eq!(None, StatusCode::parse("1234"));
+        assert_eq!(None, StatusCode::parse("2x0 OK"));
+        assert_eq!(None, StatusCode::parse("200 O\rK"));
+    }
+
+    #[test]
+    fn parse_unregistered() {
+        let status = StatusCode::parse("299").unwrap();
+        assert_eq!(299, status.as_u16());
+        assert_eq!(None, status.canonical_reason());
+        assert_eq!("299 ", status.to_string());
+
+        let status = StatusCode::parse("799 Custom Thing").unwrap();
+        assert_eq!("799 Custom Thing", status.to_string());
+    }
+
+    #[test]
+    fn parse_reason() {
+        let status = StatusCode::parse("200 Everything is fine").unwrap();
+        assert_eq!(StatusCode::OK, status);
+        assert_eq!("Everything is fine", status.reason());
+        assert_eq!("200 Everything is fine", status.to_string());
+
+        assert_eq!("200 OK", StatusCode::parse("200").unwrap().to_string());
+        assert_eq!("200 OK", StatusCode::parse("200 ").unwrap().to_string());
+    }
+
+    #[test]
+    fn parse_registry() {
+        assert_eq!(
+            Some(StatusCode::PermanentRedirect),
+            StatusCode::parse("308")
+        );
+        assert_eq!(Some(StatusCode::TooManyRequests), StatusCode::parse("429"));
+        assert_eq!(
+            "511 Network Authentication Required",
+            StatusCode::NetworkAuthenticationRequired.to_string()
+        );
+    }
+
+    #[test]
+    fn canonical_reasons() {
+        // A Sample of the Reason-Phrases in the IANA HTTP Status Code Registry
+        let registry = [
+            (100, "Continue"),
+            (203, "Non-Authoritative Information"),
+            (308, "Permanent Redirect"),
+            (408, "Request Timeout"),
+            (413, "Content Too Large"),
+            (414, "URI Too Long"),
+            (416, "Range Not Satisfiable"),
+            (421, "Misdirected Request"),
+            (422, "Unprocessable Content"),
+            (451, "Unavailable For Legal Reasons"),
+            (504, "Gateway Timeout"),
+            (505, "HTTP Version Not Supported"),
+        ];
+        for (code, reason) in registry {
+            let status = StatusCode::from_u16(code).unwrap();
+            assert_eq!(Some(reason), status.canonical_reason(), "{}", code);
+        }
+    }
+
+    #[test]
+    fn with_reason() {
+        let status = StatusCode::NotFound.with_reason("Nope").unwrap();
+        assert_eq!("404 Nope", status.to_string());
+        assert!(StatusCode::NotFound.with_reason("No\r\npe").is_none());
+    }
+
+    #[test]
+    fn classes() {
+        assert!(StatusCode::Continue.is_informational());
+        assert!(StatusCode::NoContent.is_success());
+        assert!(StatusCode::PermanentRedirect.is_redirection());
+        assert!(StatusCode::NotFound.is_client_error());
+        assert!(StatusCode::BadGateway.is_server_error());
+        assert!(!StatusCode::from_u16(600).unwrap().is_server_error());
+    }
+
+    #[test]
+    fn u16_conversions() {
+        assert_eq!(Ok(StatusCode::Created), StatusCode::try_from(201));
+        assert_eq!(
+            Err(ParseError::InvalidStatusCode),
+            StatusCode::try_from(1000)
+        );
+        assert_eq!(None, StatusCode::from_u16(99));
+        assert_eq!(404, u16::from(StatusCode::NotFound));
+    }
+
+    #[cfg(feature = "wasm_serialize")]
+    #[test]
+    fn wasm_roundtrip() {
+        assert_eq!(429, StatusCode::TooManyRequests.wasm_serialize());
+        assert_eq!(
+            Some(StatusCode::TooManyRequests),
+            StatusCode::wasm_deserialize(429)
+        );
+        assert_eq!(None, StatusCode::wasm_deserialize(-200));
     }
 
     #[test]
@@ -400,144 +593,154 @@ mod tests {
         );
     }
 
+    #[test]
+    fn serialize_into_buffer() {
+        let mut buf = Vec::new();
+        StatusCode::OK.serialize(&mut buf);
+        buf.push(b'|');
+        StatusCode::parse("799 Custom Thing")
+            .unwrap()
+            .serialize(&mut buf);
+        assert_eq!("200 OK|799 Custom Thing".as_bytes(), &buf[..]);
+    }
     #[test]
     fn serialize() {
-        assert_eq!("100 Continue".to_owned(), StatusCode::Continue.serialize());
+        assert_eq!("100 Continue".to_owned(), StatusCode::Continue.to_string());
         assert_eq!(
             "101 Switching Protocols".to_owned(),
-            StatusCode::SwitchingProtocols.serialize()
+            StatusCode::SwitchingProtocols.to_string()
         );
-        assert_eq!("200 OK".to_owned(), StatusCode::OK.serialize());
-        assert_eq!("201 Created".to_owned(), StatusCode::Created.serialize());
-        assert_eq!("202 Accepted".to_owned(), StatusCode::Accepted.serialize());
+        assert_eq!("200 OK".to_owned(), StatusCode::OK.to_string());
+        assert_eq!("201 Created".to_owned(), StatusCode::Created.to_string());
+        assert_eq!("202 Accepted".to_owned(), StatusCode::Accepted.to_string());
         assert_eq!(
             "203 Non-Authoritative Information".to_owned(),
-            StatusCode::NonAuthoritativeInformation.serialize()
+            StatusCode::NonAuthoritativeInformation.to_string()
         );
         assert_eq!(
             "204 No Content".to_owned(),
-            StatusCode::NoContent.serialize()
+            StatusCode::NoContent.to_string()
         );
         assert_eq!(
             "205 Reset Content".to_owned(),
-            StatusCode::ResetContent.serialize()
+            StatusCode::ResetContent.to_string()
         );
         assert_eq!(
             "206 Partial Content".to_owned(),
-            StatusCode::PartialContent.serialize()
+            StatusCode::PartialContent.to_string()
         );
 
         assert_eq!(
             "300 Multiple Choices".to_owned(),
-            StatusCode::MultipleChoices.serialize()
+            StatusCode::MultipleChoices.to_string()
         );
         assert_eq!(
             "301 Moved Permanently".to_owned(),
-            StatusCode::MovedPermanently.serialize()
+            StatusCode::MovedPermanently.to_string()
         );
-        assert_eq!("302 Found".to_owned(), StatusCode::Found.serialize());
-        assert_eq!("303 See Other".to_owned(), StatusCode::SeeOther.serialize());
+        assert_eq!("302 Found".to_owned(), StatusCode::Found.to_string());
+        assert_eq!("303 See Other".to_owned(), StatusCode::SeeOther.to_string());
         assert_eq!(
             "304 Not Modified".to_owned(),
-            StatusCode::NotModified.serialize()
+            StatusCode::NotModified.to_string()
         );
-        assert_eq!("305 Use Proxy".to_owned(), StatusCode::UseProxy.serialize());
+        assert_eq!("305 Use Proxy".to_owned(), StatusCode::UseProxy.to_string());
         assert_eq!(
             "307 Temporary Redirect".to_owned(),
-            StatusCode::TemporaryRedirect.serialize()
+            StatusCode::TemporaryRedirect.to_string()
         );
 
         assert_eq!(
             "400 Bad Request".to_owned(),
-            StatusCode::BadRequest.serialize()
+            StatusCode::BadRequest.to_string()
         );
         assert_eq!(
             "401 Unauthorized".to_owned(),
-            StatusCode::Unauthorized.serialize()
+            StatusCode::Unauthorized.to_string()
         );
         assert_eq!(
             "402 Payment Required".to_owned(),
-            StatusCode::PaymentRequired.serialize()
+            StatusCode::PaymentRequired.to_string()
         );
         assert_eq!(
             "403 Forbidden".to_owned(),
-            StatusCode::Forbidden.serialize()
+            StatusCode::Forbidden.to_string()
         );
-        assert_eq!("404 Not Found".to_owned(), StatusCode::NotFound.serialize());
+        assert_eq!("404 Not Found".to_owned(), StatusCode::NotFound.to_string());
         assert_eq!(
             "405 Method Not Allowed".to_owned(),
-            StatusCode::MethodNotAllowed.serialize()
+            StatusCode::MethodNotAllowed.to_string()
         );
         assert_eq!(
             "406 Not Acceptable".to_owned(),
-            StatusCode::NotAcceptable.serialize()
+            StatusCode::NotAcceptable.to_string()
         );
         assert_eq!(
             "407 Proxy Authentication Required".to_owned(),
-            StatusCode::ProxyAuthenticationRequired.serialize()
+            StatusCode::ProxyAuthenticationRequired.to_string()
         );
         assert_eq!(
-            "408 Request Time-out".to_owned(),
-            StatusCode::RequestTimeOut.serialize()
+            "408 Request Timeout".to_owned(),
+            StatusCode::RequestTimeOut.to_string()
         );
-        assert_eq!("409 Conflict".to_owned(), StatusCode::Conflict.serialize());
-        assert_eq!("410 Gone".to_owned(), StatusCode::Gone.serialize());
+        assert_eq!("409 Conflict".to_owned(), StatusCode::Conflict.to_string());
+        assert_eq!("410 Gone".to_owned(), StatusCode::Gone.to_string());
         assert_eq!(
             "411 Length Required".to_owned(),
-            StatusCode::LengthRequired.serialize()
+            StatusCode::LengthRequired.to_string()
         );
         assert_eq!(
             "412 Precondition Failed".to_owned(),
-            StatusCode::PreconditionFailed.serialize()
+            StatusCode::PreconditionFailed.to_string()
         );
         assert_eq!(
-            "413 Request Entity Too Large".to_owned(),
-            StatusCode::RequestEntityTooLarge.serialize()
+            "413 Content Too Large".to_owned(),
+            StatusCode::RequestEntityTooLarge.to_string()
         );
         assert_eq!(
-            "414 Request-URI Too Large".to_owned(),
-            StatusCode::RequestURITooLarge.serialize()
+            "414 URI Too Long".to_owned(),
+            StatusCode::RequestURITooLarge.to_string()
         );
         assert_eq!(
             "415 Unsupported Media Type".to_owned(),
-            StatusCode::UnsupportedMediaType.serialize()
+            StatusCode::UnsupportedMediaType.to_string()
         );
         assert_eq!(
-            "416 Requested Range Not Satisfiable".to_owned(),
-            StatusCode::RequestedRangeNotSatisfiable.serialize()
+            "416 Range Not Satisfiable".to_owned(),
+            StatusCode::RequestedRangeNotSatisfiable.to_string()
         );
         assert_eq!(
             "417 Expectation Failed".to_owned(),
-            StatusCode::ExpectationFailed.serialize()
+            StatusCode::ExpectationFailed.to_string()
         );
         assert_eq!(
             "418 I'm a Teapot".to_owned(),
-            StatusCode::ImATeapot.serialize()
+            StatusCode::ImATeapot.to_string()
         );
 
         assert_eq!(
             "500 Internal Server Error".to_owned(),
-            StatusCode::InternalServerError.serialize()
+            StatusCode::InternalServerError.to_string()
         );
         assert_eq!(
             "501 Not Implemented".to_owned(),
-            StatusCode::NotImplemented.serialize()
+            StatusCode::NotImplemented.to_string()
         );
         assert_eq!(
             "502 Bad Gateway".to_owned(),
-            StatusCode::BadGateway.serialize()
+            StatusCode::BadGateway.to_string()
         );
         assert_eq!(
             "503 Service Unavailable".to_owned(),
-            StatusCode::ServiceUnavailable.serialize()
+            StatusCode::ServiceUnavailable.to_string()
         );
         assert_eq!(
-            "504 Gateway Time-out".to_owned(),
-            StatusCode::GatewayTimeout.serialize()
+            "504 Gateway Timeout".to_owned(),
+            StatusCode::GatewayTimeout.to_string()
         );
         assert_eq!(
             "505 HTTP Version Not Supported".to_owned(),
-            StatusCode::HTTPVersionNotSupported.serialize()
+            StatusCode::HTTPVersionNotSupported.to_string()
         );
     }
 }
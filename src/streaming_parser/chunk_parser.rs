@@ -1,8 +1,24 @@
-use crate::Chunk;
+use crate::streaming_parser::{ParseError, ParseProfile, ParseResult, ParserConfig, ParserLimits};
+use crate::{
+    grammar,
+    header::{HeaderKey, HeaderValue},
+    Chunk, ChunkExtension, Headers,
+};
 
 enum ParseState {
+    /// Reading the Chunk-Size Line, including any Extensions
     Size,
-    Content(usize),
+    /// Reading the Data of the Chunk
+    Content,
+    /// Expecting the CRLF after the Data, the bool indicates
+    /// if the CR was already received
+    ContentEnd(bool),
+    /// Reading the Trailer-Section after the last Chunk
+    Trailer,
+    /// A complete Chunk has been parsed
+    Done,
+    /// The Chunk was malformed
+    Error(ParseError),
 }
 
 /// A single ChunkParser instance used to parse
@@ -10,23 +26,37 @@ enum ParseState {
 pub struct ChunkParser {
     state: ParseState,
     head: Vec<u8>,
+    size: usize,
     body: Vec<u8>,
+    extensions: Vec<ChunkExtension>,
+    trailers: Headers<'static>,
+    limits: ParserLimits,
+    profile: ParseProfile,
 }
 
-/// The maximum chunk size allowed by this parser,
-/// this helps prevent attacks that try to send a very
-/// large size and cause the server to run out of
-/// memory
-const MAX_CHUNK_SIZE: usize = 64 * 2usize.pow(20); // 64 Mebibyte
-
 impl ChunkParser {
     /// Creates a new empty Instance of the ChunkParser
-    /// that is ready to start parsing Request
+    /// that is ready to start parsing Request, using the
+    /// default Limits
     pub fn new() -> ChunkParser {
+        Self::new_with_config(ParserConfig::default())
+    }
+
+    /// Creates a new empty Instance of the ChunkParser
+    /// that uses the given Configuration.
+    ///
+    /// The `max_body_size` Limit is applied to the Size of
+    /// every single Chunk
+    pub fn new_with_config(config: ParserConfig) -> ChunkParser {
         Self {
             state: ParseState::Size,
             head: Vec::with_capacity(16),
+            size: 0,
             body: Vec::new(),
+            extensions: Vec::new(),
+            trailers: Headers::new(),
+            limits: config.limits,
+            profile: config.profile,
         }
     }
 
@@ -37,42 +67,92 @@ impl ChunkParser {
         // Clear the internal buffer
         self.head.clear();
         self.body.clear();
+        self.extensions.clear();
+        self.trailers = Headers::new();
 
         // Reset the internal state
+        self.size = 0;
         self.state = ParseState::Size;
     }
 
-    /// Parses and handles each individual byte
-    fn parse_size(&mut self) -> Option<usize> {
-        match self.head.last() {
-            Some(byte) if *byte != b'\n' => return None,
-            None => return None,
-            _ => {}
+    /// Parses the complete Chunk-Size Line, which is stored
+    /// in the head Buffer, including the trailing CRLF
+    fn parse_size(&mut self) -> ParseResult<usize> {
+        let line = match strip_line_ending(self.profile, &self.head) {
+            Some(l) => l,
+            None => return Err(ParseError::InvalidChunkSize),
         };
 
-        self.head.pop();
-        self.head.pop();
+        let size_end = line.iter().position(|b| *b == b';').unwrap_or(line.len());
+        let (raw_size, raw_extensions) = line.split_at(size_end);
 
-        let head_str = match std::str::from_utf8(&self.head) {
-            Ok(t) => t,
-            Err(_) => {
-                return None;
-            }
+        // Whitespace is only allowed before an Extension
+        let raw_size = if raw_extensions.is_empty() {
+            raw_size
+        } else {
+            grammar::trim_whitespace(raw_size)
         };
+        if raw_size.is_empty() || !raw_size.iter().all(|b| b.is_ascii_hexdigit()) {
+            return Err(ParseError::InvalidChunkSize);
+        }
 
-        let result = match usize::from_str_radix(head_str, 16) {
-            Ok(n) => n,
-            Err(_) => {
-                return None;
-            }
-        };
+        let mut result: usize = 0;
+        for digit in raw_size.iter() {
+            let value = (*digit as char).to_digit(16).unwrap() as usize;
+            result = match result.checked_mul(16) {
+                Some(r) => r + value,
+                None => return Err(ParseError::InvalidChunkSize),
+            };
+        }
 
         // Safety check to prevent large Chunk sizes from allocating too much memory
-        if result > MAX_CHUNK_SIZE {
-            return None;
+        if result > self.limits.max_body_size {
+            return Err(ParseError::BodyTooLarge);
+        }
+
+        self.extensions = parse_extensions(raw_extensions)?;
+
+        Ok(result)
+    }
+
+    /// Parses a single, complete Line of the Trailer-Section
+    /// that is stored in the head Buffer
+    ///
+    /// Returns true if the Line was the empty Line that ends
+    /// the Trailer-Section
+    fn parse_trailer(&mut self) -> ParseResult<bool> {
+        let line = match strip_line_ending(self.profile, &self.head) {
+            Some(l) => l,
+            None => return Err(ParseError::InvalidTrailer),
+        };
+        if line.is_empty() {
+            return Ok(true);
+        }
+
+        let colon = match line.iter().position(|b| *b == b':') {
+            Some(c) => c,
+            None => return Err(ParseError::InvalidTrailer),
+        };
+        let raw_key = &line[..colon];
+        let raw_value = grammar::trim_whitespace(&line[colon + 1..]);
+        if !grammar::is_token(raw_key) || !grammar::is_field_value(raw_value) {
+            return Err(ParseError::InvalidTrailer);
+        }
+        if self.trailers.get_header_count() >= self.limits.max_header_count {
+            return Err(ParseError::TooManyHeaders);
         }
 
-        Some(result)
+        let key = match std::str::from_utf8(raw_key) {
+            Ok(k) => HeaderKey::parsed_owned(k),
+            Err(_) => return Err(ParseError::InvalidTrailer),
+        };
+        let value: HeaderValue = match std::str::from_utf8(raw_value) {
+            Ok(v) => v.to_owned().into(),
+            Err(_) => raw_value.to_vec().into(),
+        };
+        self.trailers.append(key, value);
+
+        Ok(false)
     }
 
     /// Parses the given Block of data,
@@ -80,48 +160,122 @@ impl ChunkParser {
     /// as if it is done with parsing
     ///
     /// Returns:
-    /// * If it is done and the `finish` function should be called
+    /// * If it is done and the `finish` function should be called,
+    ///   this is also the case if the Chunk turned out to be invalid
     /// * The amount of data that is still left in the Buffer (at the end)
     pub fn block_parse(&mut self, data: &[u8]) -> (bool, usize) {
-        match self.state {
-            ParseState::Size => {
-                for (index, tmp) in data.iter().enumerate() {
-                    self.head.push(*tmp);
-                    if let Some(n_size) = self.parse_size() {
-                        let n_state = ParseState::Content(n_size);
-                        self.state = n_state;
-                        self.body.reserve(n_size);
-                        return self.block_parse(&data[index + 1..]);
+        let mut index = 0;
+        while index < data.len() {
+            match self.state {
+                ParseState::Size => {
+                    let byte = data[index];
+                    index += 1;
+
+                    self.head.push(byte);
+                    if byte != b'\n' {
+                        if self.head.len() > self.limits.max_header_size {
+                            self.state = ParseState::Error(ParseError::HeaderTooLarge);
+                        }
+                        continue;
                     }
+
+                    match self.parse_size() {
+                        Ok(0) => {
+                            self.state = ParseState::Trailer;
+                        }
+                        Ok(n_size) => {
+                            self.size = n_size;
+                            self.body.reserve(n_size);
+                            self.state = ParseState::Content;
+                        }
+                        Err(e) => {
+                            self.state = ParseState::Error(e);
+                        }
+                    };
+                    self.head.clear();
+                }
+                ParseState::Content => {
+                    let left_to_read = self.size - self.body.len();
+                    let read_size = std::cmp::min(left_to_read, data.len() - index);
+
+                    self.body.extend_from_slice(&data[index..index + read_size]);
+                    index += read_size;
+
+                    if self.body.len() >= self.size {
+                        self.state = ParseState::ContentEnd(false);
+                    }
+                }
+                ParseState::ContentEnd(received_cr) => {
+                    let byte = data[index];
+                    index += 1;
+
+                    self.state = match (received_cr, byte) {
+                        (false, b'\r') => ParseState::ContentEnd(true),
+                        (true, b'\n') => ParseState::Done,
+                        (false, b'\n') if self.profile == ParseProfile::Lenient => ParseState::Done,
+                        _ => ParseState::Error(ParseError::InvalidChunkTerminator),
+                    };
+                }
+                ParseState::Trailer => {
+                    let byte = data[index];
+                    index += 1;
+
+                    self.head.push(byte);
+                    if byte != b'\n' {
+                        if self.head.len() > self.limits.max_header_size {
+                            self.state = ParseState::Error(ParseError::HeaderTooLarge);
+                        }
+                        continue;
+                    }
+
+                    match self.parse_trailer() {
+                        Ok(true) => {
+                            self.state = ParseState::Done;
+                        }
+                        Ok(false) => {}
+                        Err(e) => {
+                            self.state = ParseState::Error(e);
+                        }
+                    };
+                    self.head.clear();
+                }
+                ParseState::Done | ParseState::Error(_) => {
+                    return (true, data.len() - index);
                 }
-                (false, 0)
-            }
-            ParseState::Content(size) => {
-                let body_length = self.body.len();
-                let left_to_read = size - body_length;
-
-                let data_length = data.len();
-                let read_size = std::cmp::min(left_to_read, data_length);
-
-                self.body.extend_from_slice(&data[..read_size]);
-                (
-                    self.body.len() >= size,
-                    data_length.saturating_sub(read_size + 2),
-                )
             }
         }
+
+        match self.state {
+            ParseState::Done | ParseState::Error(_) => (true, 0),
+            _ => (false, 0),
+        }
     }
 
     /// Finishes the Parsing and returns the
     /// finsihed Chunk
-    pub fn finish(&mut self) -> Option<Chunk> {
-        let size = match self.state {
-            ParseState::Size => return None,
-            ParseState::Content(s) => s,
+    ///
+    /// ## Errors
+    /// Returns the Error that stopped the Parsing, or
+    /// `IncompleteChunk` if the Chunk has not been received
+    /// completely yet
+    pub fn finish(&mut self) -> ParseResult<Chunk> {
+        match &self.state {
+            ParseState::Done => {}
+            ParseState::Error(e) => return Err(e.clone()),
+            _ => return Err(ParseError::IncompleteChunk),
         };
 
         let body = std::mem::take(&mut self.body);
-        Some(Chunk::new(size, body))
+        let mut chunk = if self.size == 0 {
+            Chunk::last(std::mem::take(&mut self.trailers))
+        } else {
+            Chunk::new(self.size, body)
+        };
+        for extension in self.extensions.drain(..) {
+            chunk.add_extension(extension);
+        }
+
+        Ok(chunk)
     }
 }
 
@@ -131,19 +285,110 @@ impl Default for ChunkParser {
     }
 }
 
+/// Removes the Line-Ending from the given complete Line, returns
+/// None if the Line-Ending is not valid for the Profile
+fn strip_line_ending(profile: ParseProfile, line: &[u8]) -> Option<&[u8]> {
+    match profile.line_end(line) {
+        Ok(Some(end)) => Some(&line[..end]),
+        _ => None,
+    }
+}
+
+/// Parses all the Extensions of a single Chunk, the given Data
+/// should start at the first `;`
+fn parse_extensions(mut raw: &[u8]) -> ParseResult<Vec<ChunkExtension>> {
+    let mut result = Vec::new();
+
+    loop {
+        raw = grammar::trim_whitespace(raw);
+        raw = match raw.split_first() {
+            None => return Ok(result),
+            Some((b';', rest)) => grammar::trim_whitespace(rest),
+            Some(_) => return Err(ParseError::InvalidChunkExtension),
+        };
+
+        let name_end = raw
+            .iter()
+            .position(|b| !grammar::is_tchar(*b))
+            .unwrap_or(raw.len());
+        if name_end == 0 {
+            return Err(ParseError::InvalidChunkExtension);
+        }
+        // Only consists of tchars, so this is always valid UTF-8
+        let name = std::str::from_utf8(&raw[..name_end]).unwrap().to_owned();
+        raw = grammar::trim_whitespace(&raw[name_end..]);
+
+        let value = match raw.split_first() {
+            Some((b'=', rest)) => {
+                let (value, rest) = parse_extension_value(grammar::trim_whitespace(rest))?;
+                raw = rest;
+                Some(value)
+            }
+            _ => None,
+        };
+
+        result.push(ChunkExtension::new(name, value));
+    }
+}
+
+/// Parses a single Extension-Value, which is either a `token` or
+/// a `quoted-string`, and returns the Value as well as the rest
+/// of the Data
+fn parse_extension_value(raw: &[u8]) -> ParseResult<(String, &[u8])> {
+    if raw.first() != Some(&b'"') {
+        let end = raw
+            .iter()
+            .position(|b| !grammar::is_tchar(*b))
+            .unwrap_or(raw.len());
+        if end == 0 {
+            return Err(ParseError::InvalidChunkExtension);
+        }
+
+        // Only consists of tchars, so this is always valid UTF-8
+        let value = std::str::from_utf8(&raw[..end]).unwrap().to_owned();
+        return Ok((value, &raw[end..]));
+    }
+
+    let mut value = Vec::new();
+    let mut index = 1;
+    while index < raw.len() {
+        match raw[index] {
+            b'"' => {
+                return match String::from_utf8(value) {
+                    Ok(v) => Ok((v, &raw[index + 1..])),
+                    Err(_) => Err(ParseError::InvalidChunkExtension),
+                };
+            }
+            b'\\' if index + 1 < raw.len() => {
+                value.push(raw[index + 1]);
+                index += 2;
+            }
+            byte if byte == b'\t' || (byte >= b' ' && byte != 0x7f) => {
+                value.push(byte);
+                index += 1;
+            }
+            _ => return Err(ParseError::InvalidChunkExtension),
+        };
+    }
+
+    // The closing Quote was never found
+    Err(ParseError::InvalidChunkExtension)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::header::HeaderValue;
 
     #[test]
     fn parse_valid_chunk() {
         let content = "9\r\nDeveloper\r\n".as_bytes();
 
         let mut parser = ChunkParser::new();
-        assert_eq!((true, 0), parser.block_parse(&content));
+        assert_eq!((true, 0), parser.block_parse(content));
 
         assert_eq!(
-            Some(Chunk::new(9, "Developer".as_bytes().to_vec())),
+            Ok(Chunk::new(9, "Developer".as_bytes().to_vec())),
             parser.finish()
         );
     }
@@ -152,9 +397,9 @@ mod tests {
         let content = "0\r\n\r\n".as_bytes();
 
         let mut parser = ChunkParser::new();
-        assert_eq!((true, 0), parser.block_parse(&content));
+        assert_eq!((true, 0), parser.block_parse(content));
 
-        assert_eq!(Some(Chunk::new(0, "".as_bytes().to_vec())), parser.finish());
+        assert_eq!(Ok(Chunk::last(Headers::new())), parser.finish());
     }
 
     #[test]
@@ -162,10 +407,10 @@ mod tests {
         let content = "9\r\nDeveloper\r\n0\r\n\r\n".as_bytes();
 
         let mut parser = ChunkParser::new();
-        assert_eq!((true, 5), parser.block_parse(&content));
+        assert_eq!((true, 5), parser.block_parse(content));
 
         assert_eq!(
-            Some(Chunk::new(9, "Developer".as_bytes().to_vec())),
+            Ok(Chunk::new(9, "Developer".as_bytes().to_vec())),
             parser.finish()
         );
     }
@@ -173,12 +418,178 @@ mod tests {
     #[test]
     fn parse_valid_multiple_chunks() {
         let mut parser = ChunkParser::new();
-        assert_eq!((false, 0), parser.block_parse(&"9\r\nDevel".as_bytes()));
-        assert_eq!((true, 0), parser.block_parse(&"oper\r\n".as_bytes()));
+        assert_eq!((false, 0), parser.block_parse("9\r\nDevel".as_bytes()));
+        assert_eq!((true, 0), parser.block_parse("oper\r\n".as_bytes()));
+
+        assert_eq!(
+            Ok(Chunk::new(9, "Developer".as_bytes().to_vec())),
+            parser.finish()
+        );
+    }
+
+    #[test]
+    fn parse_chunk_with_extensions() {
+        let content =
+            "9;name=value ; quoted=\"some \\\"value\\\"\";flag\r\nDeveloper\r\n".as_bytes();
+
+        let mut parser = ChunkParser::new();
+        assert_eq!((true, 0), parser.block_parse(content));
+
+        let chunk = parser.finish().unwrap();
+        assert_eq!("Developer".as_bytes(), chunk.body());
+        assert_eq!(
+            &[
+                ChunkExtension::new("name", Some("value")),
+                ChunkExtension::new("quoted", Some("some \"value\"")),
+                ChunkExtension::new("flag", None::<String>),
+            ],
+            chunk.extensions()
+        );
+    }
+    #[test]
+    fn parse_chunk_invalid_extension() {
+        let mut parser = ChunkParser::new();
+        assert_eq!((true, 0), parser.block_parse("9;=value\r\n".as_bytes()));
+        assert_eq!(Err(ParseError::InvalidChunkExtension), parser.finish());
+
+        let mut parser = ChunkParser::new();
+        assert_eq!(
+            (true, 0),
+            parser.block_parse("9;name=\"open\r\n".as_bytes())
+        );
+        assert_eq!(Err(ParseError::InvalidChunkExtension), parser.finish());
+    }
+
+    #[test]
+    fn parse_last_chunk_with_trailers() {
+        let content = "0\r\ngrpc-status: 0\r\ngrpc-message:  OK \r\n\r\nrest".as_bytes();
+
+        let mut parser = ChunkParser::new();
+        assert_eq!((true, 4), parser.block_parse(content));
+
+        let chunk = parser.finish().unwrap();
+        assert!(chunk.is_last());
+
+        let trailers = chunk.trailers().unwrap();
+        assert_eq!(2, trailers.get_header_count());
+        assert_eq!(
+            Some(&HeaderValue::Str("0".to_owned())),
+            trailers.get("grpc-status")
+        );
+        assert_eq!(
+            Some(&HeaderValue::Str("OK".to_owned())),
+            trailers.get("grpc-message")
+        );
+    }
+    #[test]
+    fn parse_last_chunk_split_trailers() {
+        let mut parser = ChunkParser::new();
+        assert_eq!((false, 0), parser.block_parse("0\r\nExpires: ".as_bytes()));
+        assert_eq!((false, 0), parser.block_parse("never\r\n\r".as_bytes()));
+        assert_eq!((true, 0), parser.block_parse("\n".as_bytes()));
+
+        let chunk = parser.finish().unwrap();
+        assert_eq!(
+            Some(&HeaderValue::Str("never".to_owned())),
+            chunk.trailers().unwrap().get("Expires")
+        );
+    }
+    #[test]
+    fn parse_last_chunk_invalid_trailer() {
+        let mut parser = ChunkParser::new();
+        assert_eq!(
+            (true, 2),
+            parser.block_parse("0\r\nno colon\r\n\r\n".as_bytes())
+        );
+        assert_eq!(Err(ParseError::InvalidTrailer), parser.finish());
+    }
+
+    #[test]
+    fn parse_invalid_terminator() {
+        let mut parser = ChunkParser::new();
+        assert_eq!(
+            (true, 7),
+            parser.block_parse("3\r\nDeveloper\r\n".as_bytes())
+        );
+        assert_eq!(Err(ParseError::InvalidChunkTerminator), parser.finish());
+    }
+    #[test]
+    fn parse_invalid_size() {
+        let mut parser = ChunkParser::new();
+        assert_eq!((true, 0), parser.block_parse("xyz\r\n".as_bytes()));
+        assert_eq!(Err(ParseError::InvalidChunkSize), parser.finish());
+
+        let mut parser = ChunkParser::new();
+        assert_eq!((true, 0), parser.block_parse("+9\r\n".as_bytes()));
+        assert_eq!(Err(ParseError::InvalidChunkSize), parser.finish());
+
+        let mut parser = ChunkParser::new();
+        assert_eq!(
+            (true, 0),
+            parser.block_parse("fffffffffffffffffffff\r\n".as_bytes())
+        );
+        assert_eq!(Err(ParseError::InvalidChunkSize), parser.finish());
+    }
+    #[test]
+    fn parse_bare_line_feed() {
+        let mut parser = ChunkParser::new();
+        assert_eq!(
+            (true, 11),
+            parser.block_parse("9\nDeveloper\r\n".as_bytes())
+        );
+        assert_eq!(Err(ParseError::InvalidChunkSize), parser.finish());
 
+        let mut parser = ChunkParser::new_with_config(ParserConfig {
+            profile: ParseProfile::Lenient,
+            ..ParserConfig::default()
+        });
+        assert_eq!((true, 0), parser.block_parse("9\nDeveloper\n".as_bytes()));
         assert_eq!(
-            Some(Chunk::new(9, "Developer".as_bytes().to_vec())),
+            Ok(Chunk::new(9, "Developer".as_bytes().to_vec())),
             parser.finish()
         );
     }
+    #[test]
+    fn parse_incomplete() {
+        let mut parser = ChunkParser::new();
+        assert_eq!((false, 0), parser.block_parse("9\r\nDeveloper".as_bytes()));
+        assert_eq!(Err(ParseError::IncompleteChunk), parser.finish());
+    }
+
+    #[test]
+    fn parse_chunk_too_large() {
+        let mut config = ParserConfig::default();
+        config.limits.max_body_size = 8;
+
+        let mut parser = ChunkParser::new_with_config(config);
+        assert_eq!(
+            (true, 11),
+            parser.block_parse("9\r\nDeveloper\r\n".as_bytes())
+        );
+        assert_eq!(Err(ParseError::BodyTooLarge), parser.finish());
+    }
+    #[test]
+    fn parse_size_line_too_long() {
+        let mut config = ParserConfig::default();
+        config.limits.max_header_size = 16;
+
+        let mut parser = ChunkParser::new_with_config(config);
+        assert_eq!(
+            (true, 12),
+            parser.block_parse("9;name=some-very-long-value\r\n".as_bytes())
+        );
+        assert_eq!(Err(ParseError::HeaderTooLarge), parser.finish());
+    }
+    #[test]
+    fn parse_too_many_trailers() {
+        let mut config = ParserConfig::default();
+        config.limits.max_header_count = 1;
+
+        let mut parser = ChunkParser::new_with_config(config);
+        assert_eq!(
+            (true, 2),
+            parser.block_parse("0\r\nfirst: 1\r\nsecond: 2\r\n\r\n".as_bytes())
+        );
+        assert_eq!(Err(ParseError::TooManyHeaders), parser.finish());
+    }
 }
@@ -0,0 +1,122 @@
+use crate::streaming_parser::{ParseError, ParseResult};
+
+/// The Limits that are enforced by the Parsers while parsing,
+/// to protect against Peers that try to exhaust the Memory
+/// of the receiving Side
+///
+/// All the Sizes are in bytes
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParserLimits {
+    /// The maximum Length of the Request-Line (or Status-Line
+    /// for Responses)
+    pub max_request_line_length: usize,
+    /// The maximum Number of Headers
+    pub max_header_count: usize,
+    /// The maximum Size of a single Header-Line, this also applies
+    /// to Chunk-Size Lines and Trailer-Fields
+    pub max_header_size: usize,
+    /// The maximum Size of the entire Head (First-Line + Headers)
+    pub max_head_size: usize,
+    /// The maximum Size of the Body, this also applies to the
+    /// Body of chunked Messages
+    pub max_body_size: usize,
+}
+
+impl Default for ParserLimits {
+    fn default() -> Self {
+        Self {
+            max_request_line_length: 8 * 1024, // 8 Kibibyte
+            max_header_count: 100,
+            max_header_size: 8 * 1024,          // 8 Kibibyte
+            max_head_size: 64 * 1024,           // 64 Kibibyte
+            max_body_size: 64 * 2usize.pow(20), // 64 Mebibyte
+        }
+    }
+}
+
+/// Determines how strictly the Parsers follow the Grammar
+/// defined in [RFC 9112](https://www.rfc-editor.org/rfc/rfc9112)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParseProfile {
+    /// Rejects bare LF Line-Endings, empty Lines before the
+    /// Request-Line, obsolete Line-Folding and any Whitespace
+    /// other than a single Space as a Separator
+    #[default]
+    Strict,
+    /// Accepts the Deviations that are rejected by `Strict`
+    /// and normalises them, as allowed by
+    /// [RFC 9112 2.2](https://www.rfc-editor.org/rfc/rfc9112#section-2.2)
+    Lenient,
+}
+
+impl ParseProfile {
+    /// Checks if the given Byte separates the Parts of the
+    /// Request-Line or Status-Line, any Whitespace other than a
+    /// Space is rejected in Strict-Mode
+    pub(crate) fn is_separator(self, byte: u8) -> ParseResult<bool> {
+        match byte {
+            b' ' => Ok(true),
+            b'\t' | 0x0B | 0x0C if self == Self::Lenient => Ok(true),
+            b'\t' | 0x0B | 0x0C => Err(ParseError::InvalidWhitespace),
+            _ => Ok(false),
+        }
+    }
+
+    /// Checks if the last Byte in the Buffer ends a Line and
+    /// returns the End of the Line's Content (without the CRLF)
+    ///
+    /// A CR that is not followed by a LF is always rejected, a bare
+    /// LF is only accepted by the `Lenient` Profile
+    pub(crate) fn line_end(self, buffer: &[u8]) -> ParseResult<Option<usize>> {
+        let current = match buffer.len().checked_sub(1) {
+            Some(c) => c,
+            None => return Ok(None),
+        };
+        let previous_cr = current > 0 && buffer[current - 1] == b'\r';
+
+        match buffer[current] {
+            b'\n' if previous_cr => Ok(Some(current - 1)),
+            b'\n' if self == Self::Lenient => Ok(Some(current)),
+            b'\n' => Err(ParseError::InvalidLineEnding),
+            _ if previous_cr => Err(ParseError::InvalidLineEnding),
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Determines how the Parsers deal with Headers that are
+/// received more than once
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DuplicatePolicy {
+    /// Keeps every Header-Line as a separate Header
+    #[default]
+    Keep,
+    /// Rejects duplicated Singleton-Fields, see
+    /// [`StandardHeader::is_singleton`](crate::header::StandardHeader::is_singleton),
+    /// and keeps all other Headers separate
+    ///
+    /// A duplicated `Host` is rejected with
+    /// [`ParseError::DuplicateHost`](crate::streaming_parser::ParseError::DuplicateHost)
+    RejectSingletons,
+    /// Rejects duplicated Singleton-Fields like `RejectSingletons` and
+    /// combines the Values of List-Fields into the first Header, see
+    /// [`StandardHeader::is_list`](crate::header::StandardHeader::is_list)
+    MergeLists,
+}
+
+/// The Configuration used by the Parsers
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ParserConfig {
+    /// The Limits the Parser enforces
+    pub limits: ParserLimits,
+    /// How strictly the Grammar is enforced
+    pub profile: ParseProfile,
+    /// How Headers that are received more than once are handled
+    pub duplicates: DuplicatePolicy,
+    /// Whether the ReqParser checks the `Host` of the Request
+    /// when finishing, see `Request::host` for the Rules
+    pub validate_host: bool,
+    /// Whether the Parsers keep the raw Head of the Message, see
+    /// [`RawHead`](crate::RawHead) for the Details
+    pub preserve_raw: bool,
+}
@@ -0,0 +1,140 @@
+use crate::{
+    header::{HeaderKey, HeaderValue, StandardHeader},
+    streaming_parser::{DuplicatePolicy, ParseError, ParseResult},
+    Headers,
+};
+
+/// Appends a parsed Header to the Collection, while applying the
+/// given Policy if a Header with the same Key is already present
+pub(crate) fn append<'a>(
+    headers: &mut Headers<'a>,
+    key: HeaderKey<'a>,
+    value: HeaderValue<'a>,
+    policy: DuplicatePolicy,
+) -> ParseResult<()> {
+    if policy == DuplicatePolicy::Keep {
+        headers.append(key, value);
+        return Ok(());
+    }
+
+    let entry = headers.entry(key);
+    if !entry.is_present() {
+        entry.or_insert(value);
+        return Ok(());
+    }
+
+    // Only the exact canonical Names are parsed as Standard-Headers,
+    // so any other Spelling needs to be looked up again
+    let standard = match entry.key() {
+        HeaderKey::Standard(standard) => Some(*standard),
+        other => StandardHeader::from_name(other.as_ref()),
+    };
+    match standard {
+        Some(StandardHeader::Host) => Err(ParseError::DuplicateHost),
+        Some(standard) if standard.is_singleton() => Err(ParseError::DuplicateHeader),
+        Some(standard) if standard.is_list() && policy == DuplicatePolicy::MergeLists => {
+            entry.append_to_list(value);
+            Ok(())
+        }
+        _ => {
+            let key = entry.key().clone();
+            headers.append(key, value);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(
+        lines: &[(&'static str, &'static str)],
+        policy: DuplicatePolicy,
+    ) -> ParseResult<Headers<'static>> {
+        let mut headers = Headers::new();
+        for (key, value) in lines {
+            append(
+                &mut headers,
+                HeaderKey::parsed(key),
+                (*value).into(),
+                policy,
+            )?;
+        }
+        Ok(headers)
+    }
+
+    #[test]
+    fn keep() {
+        let headers = parse(&[("Host", "a"), ("host", "b")], DuplicatePolicy::Keep).unwrap();
+        assert_eq!(2, headers.get_header_count());
+    }
+
+    #[test]
+    fn reject_singletons() {
+        let policy = DuplicatePolicy::RejectSingletons;
+        assert_eq!(
+            Err(ParseError::DuplicateHost),
+            parse(&[("Host", "a"), ("host", "b")], policy)
+        );
+        assert_eq!(
+            Err(ParseError::DuplicateHeader),
+            parse(&[("content-type", "a"), ("Content-Type", "a")], policy)
+        );
+        assert_eq!(
+            Err(ParseError::DuplicateHeader),
+            parse(&[("Authorization", "a"), ("Authorization", "b")], policy)
+        );
+
+        let headers = parse(&[("Accept", "a"), ("accept", "b")], policy).unwrap();
+        assert_eq!(2, headers.get_header_count());
+    }
+
+    #[test]
+    fn merge_lists() {
+        let policy = DuplicatePolicy::MergeLists;
+        let headers = parse(
+            &[
+                ("Accept", "a"),
+                ("Set-Cookie", "id=1; Expires=Wed, 21 Oct 2015 07:28:00 GMT"),
+                ("accept", "b"),
+                ("Set-Cookie", "other=2"),
+                ("X-Custom", "a"),
+                ("X-Custom", "b"),
+                ("ACCEPT", "c"),
+            ],
+            policy,
+        )
+        .unwrap();
+
+        assert_eq!(5, headers.get_header_count());
+        assert_eq!(
+            Some(&HeaderValue::Str("a, b, c".to_owned())),
+            headers.get("Accept")
+        );
+        assert_eq!(2, headers.get_all("Set-Cookie").count());
+        assert_eq!(2, headers.get_all("X-Custom").count());
+
+        assert_eq!(
+            Err(ParseError::DuplicateHeader),
+            parse(&[("Content-Length", "1"), ("Content-Length", "1")], policy)
+        );
+        assert_eq!(
+            Err(ParseError::DuplicateHost),
+            parse(&[("Host", "a"), ("Host", "a")], policy)
+        );
+
+        let mut headers = parse(&[("Via", "1.1 first")], policy).unwrap();
+        append(
+            &mut headers,
+            HeaderKey::parsed("Via"),
+            HeaderValue::BytesRef(b"1.1 caf\xe9"),
+            policy,
+        )
+        .unwrap();
+        assert_eq!(
+            Some(&HeaderValue::Bytes(b"1.1 first, 1.1 caf\xe9".to_vec())),
+            headers.get("Via")
+        );
+    }
+}
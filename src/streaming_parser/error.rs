@@ -7,27 +7,141 @@ pub type ParseResult<T> = Result<T, ParseError>;
 pub enum ParseError {
     /// Could not find a valid Method in the Request
     MissingMethod,
+    /// The Method of the Request is not a valid Token
+    InvalidMethod,
     /// Could not find a valid Path in the Request
     MissingPath,
+    /// The Request-Target is malformed
+    InvalidTarget,
+    /// The Form of the Request-Target is not allowed for the
+    /// Method of the Request
+    TargetFormMismatch,
+    /// A percent-encoded Triplet is malformed or the decoded
+    /// Data is not valid UTF-8
+    InvalidPercentEncoding,
+    /// The Request has no `Host` Header
+    MissingHost,
+    /// The Request has multiple `Host` Headers
+    DuplicateHost,
+    /// The Host is malformed
+    InvalidHost,
+    /// The `Host` Header does not match the Authority of the
+    /// Request-Target
+    HostMismatch,
     /// Could not identify the Protocol of the Request/Response
     MissingProtocol,
+    /// The HTTP-Version of the Request/Response is not valid
+    InvalidVersion,
     /// Could not find any headers in the Request/Response
     MissingHeaders,
     /// Could not find a StatusCode in the Response
     MissingStatusCode,
     /// Returned StatusCode is not valid
     InvalidStatusCode,
+    /// The Size of a Chunk is not a valid hexadecimal Number
+    /// or is too large
+    InvalidChunkSize,
+    /// An Extension of a Chunk is malformed
+    InvalidChunkExtension,
+    /// The Data of a Chunk was not followed by a CRLF
+    InvalidChunkTerminator,
+    /// A Trailer-Field after the last Chunk is malformed
+    InvalidTrailer,
+    /// The Chunk has not been fully received yet
+    IncompleteChunk,
+    /// The Request-Line (or Status-Line) is longer than allowed
+    RequestLineTooLong,
+    /// There are more Headers than allowed
+    TooManyHeaders,
+    /// A single Header-Line is larger than allowed
+    HeaderTooLarge,
+    /// The entire Head is larger than allowed
+    HeadTooLarge,
+    /// The Body is larger than allowed
+    BodyTooLarge,
+    /// The Content-Length is not a valid Number
+    InvalidContentLength,
+    /// There are multiple Content-Length Values that differ
+    ConflictingContentLength,
+    /// Some part of the Head is not valid UTF-8
+    InvalidEncoding,
+    /// The Request contains both a Transfer-Encoding and a
+    /// Content-Length Header
+    ContentLengthWithTransferEncoding,
+    /// The Transfer-Encoding does not end with `chunked` or
+    /// applies it more than once
+    InvalidTransferEncoding,
+    /// There is Whitespace between a Header-Name and the Colon
+    WhitespaceBeforeColon,
+    /// A Header that determines the Framing of the Body
+    /// (Content-Length or Transfer-Encoding) was send multiple times
+    DuplicateFramingHeader,
+    /// A Header that may only be send once, like `Content-Type`,
+    /// was send multiple times
+    DuplicateHeader,
+    /// A Line ended with a bare LF (only rejected in Strict-Mode)
+    /// or contained a CR that was not followed by a LF
+    InvalidLineEnding,
+    /// There are empty Lines before the Request-Line or Status-Line
+    LeadingEmptyLine,
+    /// A Header-Value was continued using obsolete Line-Folding
+    ObsoleteLineFolding,
+    /// The Parts of the Request-Line or Status-Line are not
+    /// separated by a single Space
+    InvalidWhitespace,
+    /// A Header-Line is malformed, like missing the Colon
+    InvalidHeader,
+    /// The Name of a Header is not a valid Token
+    InvalidHeaderName,
+    /// The Value of a Header contains Control-Characters
+    InvalidHeaderValue,
 }
 
 impl std::fmt::Display for ParseError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match *self {
             Self::MissingMethod => write!(f, "Missing Method"),
+            Self::InvalidMethod => write!(f, "Invalid Method"),
             Self::MissingPath => write!(f, "Missing Path"),
+            Self::InvalidTarget => write!(f, "Invalid Request-Target"),
+            Self::TargetFormMismatch => write!(f, "Request-Target Form does not match Method"),
+            Self::InvalidPercentEncoding => write!(f, "Invalid Percent-Encoding"),
+            Self::MissingHost => write!(f, "Missing Host"),
+            Self::DuplicateHost => write!(f, "Duplicate Host"),
+            Self::InvalidHost => write!(f, "Invalid Host"),
+            Self::HostMismatch => write!(f, "Host does not match Request-Target"),
             Self::MissingProtocol => write!(f, "Missing Protocol"),
+            Self::InvalidVersion => write!(f, "Invalid Version"),
             Self::MissingHeaders => write!(f, "Missing Headers"),
             Self::MissingStatusCode => write!(f, "Missing StatusCode"),
             Self::InvalidStatusCode => write!(f, "Invalid StatusCode"),
+            Self::InvalidChunkSize => write!(f, "Invalid Chunk-Size"),
+            Self::InvalidChunkExtension => write!(f, "Invalid Chunk-Extension"),
+            Self::InvalidChunkTerminator => write!(f, "Invalid Chunk-Terminator"),
+            Self::InvalidTrailer => write!(f, "Invalid Trailer"),
+            Self::IncompleteChunk => write!(f, "Incomplete Chunk"),
+            Self::RequestLineTooLong => write!(f, "Request-Line too long"),
+            Self::TooManyHeaders => write!(f, "Too many Headers"),
+            Self::HeaderTooLarge => write!(f, "Header too large"),
+            Self::HeadTooLarge => write!(f, "Head too large"),
+            Self::BodyTooLarge => write!(f, "Body too large"),
+            Self::InvalidContentLength => write!(f, "Invalid Content-Length"),
+            Self::ConflictingContentLength => write!(f, "Conflicting Content-Length"),
+            Self::InvalidEncoding => write!(f, "Invalid Encoding"),
+            Self::ContentLengthWithTransferEncoding => {
+                write!(f, "Content-Length with Transfer-Encoding")
+            }
+            Self::InvalidTransferEncoding => write!(f, "Invalid Transfer-Encoding"),
+            Self::WhitespaceBeforeColon => write!(f, "Whitespace before Colon"),
+            Self::DuplicateFramingHeader => write!(f, "Duplicate Framing-Header"),
+            Self::DuplicateHeader => write!(f, "Duplicate Header"),
+            Self::InvalidLineEnding => write!(f, "Invalid Line-Ending"),
+            Self::LeadingEmptyLine => write!(f, "Leading empty Line"),
+            Self::ObsoleteLineFolding => write!(f, "Obsolete Line-Folding"),
+            Self::InvalidWhitespace => write!(f, "Invalid Whitespace"),
+            Self::InvalidHeader => write!(f, "Invalid Header"),
+            Self::InvalidHeaderName => write!(f, "Invalid Header-Name"),
+            Self::InvalidHeaderValue => write!(f, "Invalid Header-Value"),
         }
     }
 }
@@ -0,0 +1,204 @@
+use crate::grammar;
+use crate::streaming_parser::{ParseError, ParseResult};
+
+/// Checks if the given Transfer-Encoding Value has `chunked`
+/// as its final Encoding, which means the Body is send in Chunks
+pub(crate) fn is_chunked(value: &str) -> bool {
+    match value.rsplit(',').next() {
+        Some(last) => last.trim().eq_ignore_ascii_case("chunked"),
+        None => false,
+    }
+}
+
+/// Checks if the Body of a Response is only delimited by the Server
+/// closing the Connection, based on its Status-Code and its Framing-Headers,
+/// as described in [RFC 9112 6.3](https://www.rfc-editor.org/rfc/rfc9112#section-6.3)
+pub(crate) fn reads_until_close(
+    status: u16,
+    transfer_encoding: Option<&[u8]>,
+    content_length: bool,
+) -> bool {
+    // Informational, No-Content and Not-Modified Responses never have a Body
+    if (100..200).contains(&status) || status == 204 || status == 304 {
+        return false;
+    }
+
+    match transfer_encoding {
+        Some(value) => !std::str::from_utf8(value).is_ok_and(is_chunked),
+        None => !content_length,
+    }
+}
+
+/// Validates the Value of a Transfer-Encoding Header, which is only
+/// valid if `chunked` is the final Encoding and is not applied more
+/// than once
+pub(crate) fn validate_transfer_encoding(value: &[u8]) -> ParseResult<()> {
+    let value = match std::str::from_utf8(value) {
+        Ok(v) => v,
+        Err(_) => return Err(ParseError::InvalidTransferEncoding),
+    };
+
+    let mut codings = value
+        .split(',')
+        .map(|c| c.trim_matches(|c| c == ' ' || c == '\t'));
+    let last = match codings.next_back() {
+        Some(l) => l,
+        None => return Err(ParseError::InvalidTransferEncoding),
+    };
+    if !last.eq_ignore_ascii_case("chunked") {
+        return Err(ParseError::InvalidTransferEncoding);
+    }
+
+    for coding in codings {
+        if !grammar::is_token(coding.as_bytes()) || coding.eq_ignore_ascii_case("chunked") {
+            return Err(ParseError::InvalidTransferEncoding);
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses the Value of a Content-Length Header, this also accepts
+/// a List of identical Values (`5, 5`), as allowed by
+/// [RFC 9110 8.6](https://www.rfc-editor.org/rfc/rfc9110#section-8.6)
+pub(crate) fn parse_content_length(value: &[u8]) -> ParseResult<usize> {
+    let mut result: Option<usize> = None;
+    for part in value.split(|b| *b == b',') {
+        let part = grammar::trim_whitespace(part);
+        if part.is_empty() || !part.iter().all(|b| b.is_ascii_digit()) {
+            return Err(ParseError::InvalidContentLength);
+        }
+
+        let mut length: usize = 0;
+        for digit in part.iter() {
+            length = match length
+                .checked_mul(10)
+                .and_then(|l| l.checked_add((digit - b'0') as usize))
+            {
+                Some(l) => l,
+                None => return Err(ParseError::InvalidContentLength),
+            };
+        }
+
+        match result {
+            Some(previous) if previous != length => {
+                return Err(ParseError::ConflictingContentLength);
+            }
+            _ => result = Some(length),
+        };
+    }
+
+    result.ok_or(ParseError::InvalidContentLength)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunked_only() {
+        assert!(is_chunked("chunked"));
+        assert!(is_chunked("Chunked"));
+    }
+    #[test]
+    fn chunked_in_list() {
+        assert!(is_chunked("gzip, chunked"));
+        assert!(is_chunked("gzip,chunked"));
+    }
+    #[test]
+    fn chunked_not_last() {
+        assert!(!is_chunked("chunked, gzip"));
+        assert!(!is_chunked("gzip"));
+        assert!(!is_chunked(""));
+    }
+
+    #[test]
+    fn until_close() {
+        assert!(reads_until_close(200, Some(b"gzip"), true));
+        assert!(reads_until_close(200, None, false));
+        assert!(!reads_until_close(200, Some(b"gzip, chunked"), false));
+        assert!(!reads_until_close(200, None, true));
+        assert!(!reads_until_close(204, Some(b"gzip"), false));
+        assert!(!reads_until_close(304, None, false));
+        assert!(!reads_until_close(101, None, false));
+    }
+
+    #[test]
+    fn content_length_valid() {
+        assert_eq!(Ok(0), parse_content_length(b"0"));
+        assert_eq!(Ok(22), parse_content_length(b"22"));
+        assert_eq!(Ok(22), parse_content_length(b" 22\t"));
+    }
+    #[test]
+    fn content_length_list() {
+        assert_eq!(Ok(5), parse_content_length(b"5, 5"));
+        assert_eq!(Ok(5), parse_content_length(b"5,5,5"));
+        assert_eq!(
+            Err(ParseError::ConflictingContentLength),
+            parse_content_length(b"5, 6")
+        );
+    }
+    #[test]
+    fn content_length_invalid() {
+        assert_eq!(
+            Err(ParseError::InvalidContentLength),
+            parse_content_length(b"abc")
+        );
+        assert_eq!(
+            Err(ParseError::InvalidContentLength),
+            parse_content_length(b"")
+        );
+        assert_eq!(
+            Err(ParseError::InvalidContentLength),
+            parse_content_length(b"+5")
+        );
+        assert_eq!(
+            Err(ParseError::InvalidContentLength),
+            parse_content_length(b"-5")
+        );
+        assert_eq!(
+            Err(ParseError::InvalidContentLength),
+            parse_content_length(b"1 2")
+        );
+        assert_eq!(
+            Err(ParseError::InvalidContentLength),
+            parse_content_length(b"5,")
+        );
+    }
+    #[test]
+    fn content_length_overflow() {
+        assert_eq!(
+            Err(ParseError::InvalidContentLength),
+            parse_content_length(b"999999999999999999999999999999")
+        );
+    }
+
+    #[test]
+    fn transfer_encoding_valid() {
+        assert_eq!(Ok(()), validate_transfer_encoding(b"chunked"));
+        assert_eq!(Ok(()), validate_transfer_encoding(b"gzip, Chunked"));
+    }
+    #[test]
+    fn transfer_encoding_invalid() {
+        assert_eq!(
+            Err(ParseError::InvalidTransferEncoding),
+            validate_transfer_encoding(b"gzip")
+        );
+        assert_eq!(
+            Err(ParseError::InvalidTransferEncoding),
+            validate_transfer_encoding(b"chunked, gzip")
+        );
+        assert_eq!(
+            Err(ParseError::InvalidTransferEncoding),
+            validate_transfer_encoding(b"chunked, chunked")
+        );
+        assert_eq!(
+            Err(ParseError::InvalidTransferEncoding),
+            validate_transfer_encoding(b", chunked")
+        );
+        assert_eq!(
+            Err(ParseError::InvalidTransferEncoding),
+            validate_transfer_encoding(b"\xffchunked")
+        );
+    }
+}
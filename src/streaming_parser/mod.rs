@@ -7,5 +7,11 @@ pub use resp_parser::RespParser;
 mod chunk_parser;
 pub use chunk_parser::ChunkParser;
 
+mod config;
+pub use config::{DuplicatePolicy, ParseProfile, ParserConfig, ParserLimits};
+
+pub(crate) mod duplicates;
+pub(crate) mod framing;
+
 mod error;
 pub use error::{ParseError, ParseResult};
@@ -1,5 +1,14 @@
-use crate::streaming_parser::{ParseError, ParseResult};
-use crate::{header::HeaderKey, Headers, Method, Request};
+use crate::streaming_parser::{
+    duplicates, framing, ChunkParser, ParseError, ParseProfile, ParseResult, ParserConfig,
+};
+use crate::{
+    grammar,
+    header::{self, HeaderKey, HeaderValue},
+    inline_vec::InlineVec,
+    raw,
+    uri::RequestTarget,
+    Headers, Method, RawHead, Request, Span, Version,
+};
 
 type MethodState = (usize, usize);
 type PathState = (usize, usize);
@@ -7,17 +16,23 @@ type ProtocolState = (usize, usize);
 type HeaderKeyState = (usize, usize);
 
 enum State {
-    Nothing,
-    MethodParsed(MethodState),
-    PathParsed(MethodState, PathState),
+    /// The Method starts at the given Position
+    Nothing(usize),
+    /// The Path starts at the given Position
+    MethodParsed(MethodState, usize),
+    /// The Protocol starts at the given Position
+    PathParsed(MethodState, PathState, usize),
+    /// The current Header-Line starts at the given Position
     HeaderKey(MethodState, PathState, ProtocolState, usize),
     HeaderValue(MethodState, PathState, ProtocolState, HeaderKeyState),
-    HeadersParsed(MethodState, PathState, ProtocolState, usize),
+    HeadersParsed(MethodState, PathState, ProtocolState),
 }
 
 enum ProgressState {
     Head,
     Body(usize),
+    /// The Body is send using `Transfer-Encoding: chunked`
+    Chunked,
     Done,
 }
 
@@ -26,7 +41,14 @@ enum ProgressState {
 pub struct ReqParser {
     buffer: Vec<u8>,
     body_buffer: Vec<u8>,
-    headers_buf: Vec<((usize, usize), (usize, usize))>,
+    headers_buf: InlineVec<((usize, usize), (usize, usize)), 16>,
+    /// The original Bytes of every obsolete Line-Folding that was
+    /// replaced in the Buffer, only kept if the raw Head is preserved
+    folds: Vec<(usize, Vec<u8>)>,
+    chunk_parser: ChunkParser,
+    trailers: Option<Headers<'static>>,
+    error: Option<ParseError>,
+    config: ParserConfig,
     state: State,
     progress: ProgressState,
 }
@@ -34,13 +56,25 @@ pub struct ReqParser {
 impl ReqParser {
     /// Creates a new Request-Parser with the given
     /// capacity as its pre-reserved capacity to store
-    /// the Head of the Request
+    /// the Head of the Request, using the default Configuration
     pub fn new_capacity(cap: usize) -> Self {
+        Self::new_with_config(cap, ParserConfig::default())
+    }
+
+    /// Creates a new Request-Parser with the given
+    /// capacity as its pre-reserved capacity to store
+    /// the Head of the Request, that uses the given Configuration
+    pub fn new_with_config(cap: usize, config: ParserConfig) -> Self {
         Self {
             buffer: Vec::with_capacity(cap),
             body_buffer: Vec::new(),
-            headers_buf: Vec::with_capacity(20),
-            state: State::Nothing,
+            headers_buf: InlineVec::new(),
+            folds: Vec::new(),
+            chunk_parser: ChunkParser::new_with_config(config.clone()),
+            trailers: None,
+            error: None,
+            config,
+            state: State::Nothing(0),
             progress: ProgressState::Head,
         }
     }
@@ -56,95 +90,251 @@ impl ReqParser {
         self.buffer.clear();
         self.body_buffer.clear();
         self.headers_buf.clear();
+        self.folds.clear();
+        self.chunk_parser.clear();
+        self.trailers = None;
+        self.error = None;
 
         // Sets the Progress and internal State back to the
         // beginning
-        self.state = State::Nothing;
+        self.state = State::Nothing(0);
         self.progress = ProgressState::Head;
     }
 
-    fn parse(&mut self, byte: u8, current: usize) -> ProgressState {
-        match &mut self.state {
-            State::Nothing if byte == b' ' => {
-                let end = current;
-                self.state = State::MethodParsed((0, end));
-                ProgressState::Head
-            }
-            State::MethodParsed(method) if byte == b' ' => {
-                let start = method.1;
-                let end = current;
-
-                self.state = State::PathParsed(*method, (start + 1, end));
-                ProgressState::Head
-            }
-            State::PathParsed(method, path) if byte == b'\r' => {
-                let start = path.1;
-                let end = current;
+    /// Checks if the current Byte would exceed any of the
+    /// configured Limits
+    fn check_limits(&self, current: usize) -> ParseResult<()> {
+        let limits = &self.config.limits;
+        if current >= limits.max_head_size {
+            return Err(ParseError::HeadTooLarge);
+        }
 
-                self.state = State::HeaderKey(*method, *path, (start + 1, end), end);
-                ProgressState::Head
+        match &self.state {
+            State::Nothing(start)
+            | State::MethodParsed((start, _), _)
+            | State::PathParsed((start, _), _, _)
+                if current >= *start + limits.max_request_line_length =>
+            {
+                Err(ParseError::RequestLineTooLong)
             }
-            State::HeaderKey(method, path, protocol, raw_start)
-                if current == *raw_start + 2 && byte == b'\r' =>
+            State::HeaderKey(_, _, _, line_start)
+                if current >= *line_start + limits.max_header_size =>
             {
-                self.state = State::HeadersParsed(*method, *path, *protocol, current + 2);
-                ProgressState::Head
+                Err(ParseError::HeaderTooLarge)
             }
-            State::HeaderKey(method, path, protocol, raw_start)
-                if byte == b':' && *raw_start + 2 <= current =>
+            State::HeaderValue(_, _, _, header_key)
+                if current >= header_key.0 + limits.max_header_size =>
             {
-                let start = *raw_start + 2;
-                let end = current;
+                Err(ParseError::HeaderTooLarge)
+            }
+            _ => Ok(()),
+        }
+    }
 
-                self.state = State::HeaderValue(*method, *path, *protocol, (start, end));
-                ProgressState::Head
+    fn parse(&mut self, byte: u8, current: usize) -> ProgressState {
+        match self.parse_byte(byte, current) {
+            Ok(progress) => progress,
+            Err(e) => {
+                self.error = Some(e);
+                ProgressState::Done
             }
-            State::HeaderValue(method, path, protocol, header_key)
-                if byte == b'\r' && header_key.1 + 2 <= current =>
-            {
-                let start = header_key.1 + 2;
-                let end = current;
+        }
+    }
 
-                self.headers_buf.push((*header_key, (start, end)));
-                self.state = State::HeaderKey(*method, *path, *protocol, end);
-                ProgressState::Head
+    fn parse_byte(&mut self, byte: u8, current: usize) -> ParseResult<ProgressState> {
+        self.check_limits(current)?;
+
+        let profile = self.config.profile;
+        let line_end = profile.line_end(&self.buffer[..=current])?;
+        let separator = match self.state {
+            State::Nothing(_) | State::MethodParsed(_, _) | State::PathParsed(_, _, _) => {
+                profile.is_separator(byte)?
             }
-            State::HeadersParsed(_, _, _, end) if current == *end - 1 => {
-                // The Length the body is supposed to have
-                let mut length: usize = 0;
-                for raw_header_pair in self.headers_buf.iter() {
-                    let key_pair = raw_header_pair.0;
-                    let value_pair = raw_header_pair.1;
-
-                    let key_str = match std::str::from_utf8(&self.buffer[key_pair.0..key_pair.1]) {
-                        Ok(k) => k,
-                        Err(_) => {
-                            continue;
-                        }
-                    };
-                    if HeaderKey::StrRef(key_str) != HeaderKey::StrRef("Content-Length") {
-                        continue;
+            _ => false,
+        };
+
+        match &mut self.state {
+            State::Nothing(start) => match line_end {
+                // Empty Lines before the Request-Line, as described in
+                // RFC 9112 2.2
+                Some(end) if end == *start => {
+                    if profile == ParseProfile::Strict {
+                        return Err(ParseError::LeadingEmptyLine);
+                    }
+                    *start = current + 1;
+                }
+                Some(_) => return Err(ParseError::MissingPath),
+                None if separator => {
+                    if current == *start {
+                        return Err(ParseError::MissingMethod);
+                    }
+                    self.state = State::MethodParsed((*start, current), current + 1);
+                }
+                None => {}
+            },
+            State::MethodParsed(method, path_start) => match line_end {
+                Some(_) => return Err(ParseError::MissingProtocol),
+                None if separator && current == *path_start => {
+                    if profile == ParseProfile::Strict {
+                        return Err(ParseError::InvalidWhitespace);
+                    }
+                    *path_start = current + 1;
+                }
+                None if separator => {
+                    self.state = State::PathParsed(*method, (*path_start, current), current + 1);
+                }
+                None => {}
+            },
+            State::PathParsed(method, path, protocol_start) => match line_end {
+                Some(mut end) => {
+                    // Trailing Whitespace was already rejected in Strict-Mode
+                    while end > *protocol_start
+                        && profile.is_separator(self.buffer[end - 1]) == Ok(true)
+                    {
+                        end -= 1;
+                    }
+                    if end == *protocol_start {
+                        return Err(ParseError::MissingProtocol);
+                    }
+                    if Version::parse_bytes(&self.buffer[*protocol_start..end]).is_none() {
+                        return Err(ParseError::InvalidVersion);
                     }
 
-                    let value_str =
-                        match std::str::from_utf8(&self.buffer[value_pair.0..value_pair.1]) {
-                            Ok(v) => v,
-                            Err(_) => {
-                                continue;
-                            }
+                    self.state =
+                        State::HeaderKey(*method, *path, (*protocol_start, end), current + 1);
+                }
+                None if separator => {
+                    if profile == ParseProfile::Strict {
+                        return Err(ParseError::InvalidWhitespace);
+                    }
+                    if current == *protocol_start {
+                        *protocol_start = current + 1;
+                    }
+                }
+                None => {}
+            },
+            State::HeaderKey(method, path, protocol, line_start) => {
+                if let Some(end) = line_end {
+                    if end != *line_start {
+                        return Err(ParseError::InvalidHeader);
+                    }
+
+                    // The empty Line marks the End of the Head
+                    self.state = State::HeadersParsed(*method, *path, *protocol);
+                    return self.framing();
+                }
+
+                if current == *line_start && grammar::is_whitespace(byte) {
+                    // Obsolete Line-Folding, as described in RFC 9112 5.2
+                    if profile == ParseProfile::Strict {
+                        return Err(ParseError::ObsoleteLineFolding);
+                    }
+                    let (key, value) = self.headers_buf.pop().ok_or(ParseError::InvalidHeader)?;
+
+                    // Replaces the Line-Break and the Whitespace that starts the
+                    // Continuation with Spaces to continue the previous Value
+                    if self.config.preserve_raw {
+                        // An earlier Continuation of the same Value may already be
+                        // replaced, so only the Bytes after it are still the original ones
+                        let start = match self.folds.last() {
+                            Some((start, original)) => value.1.max(start + original.len()),
+                            None => value.1,
                         };
+                        self.folds
+                            .push((start, self.buffer[start..=current].to_vec()));
+                    }
+                    for tmp in self.buffer[value.1..=current].iter_mut() {
+                        *tmp = b' ';
+                    }
+                    self.state = State::HeaderValue(*method, *path, *protocol, key);
+                } else if byte == b':' {
+                    let start = *line_start;
+                    let end = current;
 
-                    length = value_str.parse().unwrap();
-                    break;
+                    // Whitespace between the Field-Name and the Colon must be rejected,
+                    // as defined in RFC 9112 5.1
+                    if start < end && grammar::is_whitespace(self.buffer[end - 1]) {
+                        return Err(ParseError::WhitespaceBeforeColon);
+                    }
+                    if !grammar::is_token(&self.buffer[start..end]) {
+                        return Err(ParseError::InvalidHeaderName);
+                    }
+
+                    self.state = State::HeaderValue(*method, *path, *protocol, (start, end));
                 }
+            }
+            State::HeaderValue(method, path, protocol, header_key) => {
+                if let Some(end) = line_end {
+                    let value =
+                        grammar::trim_whitespace_range(&self.buffer, (header_key.1 + 1, end));
+                    if !grammar::is_field_value(&self.buffer[value.0..value.1]) {
+                        return Err(ParseError::InvalidHeaderValue);
+                    }
 
-                if length > 0 {
-                    ProgressState::Body(length)
-                } else {
-                    ProgressState::Done
+                    if self.headers_buf.len() >= self.config.limits.max_header_count {
+                        return Err(ParseError::TooManyHeaders);
+                    }
+
+                    self.headers_buf.push((*header_key, value));
+                    self.state = State::HeaderKey(*method, *path, *protocol, current + 1);
+                }
+            }
+            State::HeadersParsed(_, _, _) => return Ok(ProgressState::Done),
+        };
+
+        Ok(ProgressState::Head)
+    }
+
+    /// Determines how the Body of the Request is framed, once all the
+    /// Headers have been received.
+    ///
+    /// This enforces the Rules of
+    /// [RFC 9112 6.3](https://www.rfc-editor.org/rfc/rfc9112#section-6.3)
+    /// to protect against Request-Smuggling
+    fn framing(&self) -> ParseResult<ProgressState> {
+        let mut length: Option<usize> = None;
+        let mut transfer_encoding: Option<&[u8]> = None;
+        for raw_header_pair in self.headers_buf.iter() {
+            let key_pair = raw_header_pair.0;
+            let value_pair = raw_header_pair.1;
+
+            let key_str = match std::str::from_utf8(&self.buffer[key_pair.0..key_pair.1]) {
+                Ok(k) => k,
+                Err(_) => {
+                    continue;
+                }
+            };
+            let key = HeaderKey::StrRef(key_str);
+            let raw_value = &self.buffer[value_pair.0..value_pair.1];
+
+            if key == header::TRANSFER_ENCODING {
+                if transfer_encoding.is_some() {
+                    return Err(ParseError::DuplicateFramingHeader);
+                }
+                transfer_encoding = Some(raw_value);
+            } else if key == header::CONTENT_LENGTH {
+                if length.is_some() {
+                    return Err(ParseError::DuplicateFramingHeader);
                 }
+                length = Some(framing::parse_content_length(raw_value)?);
             }
-            _ => ProgressState::Head,
+        }
+
+        if let Some(raw_encoding) = transfer_encoding {
+            if length.is_some() {
+                return Err(ParseError::ContentLengthWithTransferEncoding);
+            }
+
+            framing::validate_transfer_encoding(raw_encoding)?;
+            return Ok(ProgressState::Chunked);
+        }
+
+        match length {
+            Some(length) if length > self.config.limits.max_body_size => {
+                Err(ParseError::BodyTooLarge)
+            }
+            Some(length) if length > 0 => Ok(ProgressState::Body(length)),
+            _ => Ok(ProgressState::Done),
         }
     }
 
@@ -153,7 +343,7 @@ impl ReqParser {
     /// Explanation:
     /// * `done`: True if the request has been fully received and parsed
     /// * `data-left-in-buffer`: The Amount of bytes at the end of the given
-    /// slice that were unused
+    ///   slice that were unused
     pub fn block_parse(&mut self, bytes: &[u8]) -> (bool, Option<usize>) {
         match self.progress {
             ProgressState::Head => {
@@ -168,7 +358,7 @@ impl ReqParser {
                             self.body_buffer.reserve(length);
                             return self.block_parse(&bytes[index + 1..]);
                         }
-                        ProgressState::Done => {
+                        ProgressState::Chunked | ProgressState::Done => {
                             return self.block_parse(&bytes[index + 1..]);
                         }
                         _ => {}
@@ -177,6 +367,38 @@ impl ReqParser {
 
                 (false, None)
             }
+            ProgressState::Chunked => {
+                let (done, left) = self.chunk_parser.block_parse(bytes);
+                if !done {
+                    return (false, None);
+                }
+                let consumed = bytes.len() - left;
+
+                let chunk = self.chunk_parser.finish();
+                self.chunk_parser.clear();
+                match chunk {
+                    Ok(mut chunk) if chunk.is_last() => {
+                        self.trailers = chunk.take_trailers();
+                        self.progress = ProgressState::Done;
+                    }
+                    Ok(chunk)
+                        if self.body_buffer.len() + chunk.size()
+                            > self.config.limits.max_body_size =>
+                    {
+                        self.error = Some(ParseError::BodyTooLarge);
+                        self.progress = ProgressState::Done;
+                    }
+                    Ok(chunk) => {
+                        self.body_buffer.extend_from_slice(chunk.body());
+                    }
+                    Err(e) => {
+                        self.error = Some(e);
+                        self.progress = ProgressState::Done;
+                    }
+                };
+
+                self.block_parse(&bytes[consumed..])
+            }
             ProgressState::Body(length) => {
                 let left_to_read = length - self.body_buffer.len();
                 if left_to_read == 0 {
@@ -196,7 +418,7 @@ impl ReqParser {
             }
             ProgressState::Done => {
                 let length = bytes.len();
-                let rest = (length > 0).then(|| length);
+                let rest = (length > 0).then_some(length);
 
                 (true, rest)
             }
@@ -210,15 +432,19 @@ impl ReqParser {
     where
         'a: 'b,
     {
+        if let Some(e) = &self.error {
+            return Err(e.clone());
+        }
+
         let (method, path, protocol) = match &self.state {
-            State::HeadersParsed(m, p, pt, _) => (m, p, pt),
-            State::Nothing => {
+            State::HeadersParsed(m, p, pt) => (m, p, pt),
+            State::Nothing(_) => {
                 return Err(ParseError::MissingMethod);
             }
-            State::MethodParsed(_) => {
+            State::MethodParsed(_, _) => {
                 return Err(ParseError::MissingPath);
             }
-            State::PathParsed(_, _) => {
+            State::PathParsed(_, _, _) => {
                 return Err(ParseError::MissingProtocol);
             }
             State::HeaderKey(_, _, _, _) | State::HeaderValue(_, _, _, _) => {
@@ -226,18 +452,20 @@ impl ReqParser {
             }
         };
 
+        let spans: [Span; 3] = [(*method).into(), (*path).into(), (*protocol).into()];
         let raw_method = &self.buffer[method.0..method.1];
         let raw_path = &self.buffer[path.0..path.1];
-        let raw_protocol = &self.buffer[protocol.0..protocol.1];
+        let raw_version = &self.buffer[protocol.0..protocol.1];
 
-        let method = unsafe { std::str::from_utf8_unchecked(raw_method) };
-        let path = unsafe { std::str::from_utf8_unchecked(raw_path) };
-        let protocol = unsafe { std::str::from_utf8_unchecked(raw_protocol) };
+        let method = std::str::from_utf8(raw_method).map_err(|_| ParseError::InvalidEncoding)?;
+        let path = std::str::from_utf8(raw_path).map_err(|_| ParseError::InvalidEncoding)?;
+        let version = Version::parse_bytes(raw_version).ok_or(ParseError::InvalidVersion)?;
 
         let parsed_method = match Method::parse(method) {
             Some(m) => m,
             None => return Err(ParseError::MissingMethod),
         };
+        let target = RequestTarget::parse(path, &parsed_method)?;
 
         let header_count = self.headers_buf.len();
         let mut headers = Headers::with_capacity(header_count);
@@ -248,28 +476,57 @@ impl ReqParser {
             let value_range = tmp_header.1;
             let raw_value = &self.buffer[value_range.0..value_range.1];
 
-            let key = unsafe { std::str::from_utf8_unchecked(raw_key) };
-            let value = unsafe { std::str::from_utf8_unchecked(raw_value) };
+            let key = std::str::from_utf8(raw_key).map_err(|_| ParseError::InvalidEncoding)?;
+            // Values with obs-text, which are not valid UTF-8, are kept as raw Bytes
+            let value: HeaderValue = match std::str::from_utf8(raw_value) {
+                Ok(value) => value.into(),
+                Err(_) => raw_value.into(),
+            };
 
-            // Use append to simply add the header at the end of the collection
-            // without checking for duplicates
-            headers.append(key, value);
+            // Duplicated Headers are handled based on the configured Policy
+            duplicates::append(
+                &mut headers,
+                HeaderKey::parsed(key),
+                value,
+                self.config.duplicates,
+            )?;
         }
 
         let body = &self.body_buffer;
 
-        Ok(Request::new(protocol, parsed_method, path, headers, body))
+        let mut request = Request::with_target(version, parsed_method, target, headers, body);
+        if self.config.preserve_raw {
+            request = request.with_raw_head(RawHead::new(
+                raw::restore_folds(&self.buffer, &self.folds),
+                spans,
+                self.headers_buf
+                    .iter()
+                    .map(|(key, value)| ((*key).into(), (*value).into())),
+            ));
+        }
+        if self.config.validate_host {
+            request.host()?;
+        }
+
+        Ok(request)
     }
 
     /// Returns the current Buffer of the Parser
     pub fn buffer(&self) -> &[u8] {
         &self.buffer
     }
+
+    /// Returns the Trailer-Fields that were send after the
+    /// last Chunk of a chunked Body, if there were any
+    pub fn trailers(&self) -> Option<&Headers<'static>> {
+        self.trailers.as_ref()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{header::HeaderValue, streaming_parser::DuplicatePolicy, uri::TargetForm};
 
     #[test]
     fn parser_parse_no_body() {
@@ -282,7 +539,7 @@ mod tests {
         headers.set("Test-1", "Value-1");
         assert_eq!(
             Ok(Request::new(
-                "HTTP/1.1",
+                Version::Http11,
                 Method::GET,
                 "/path/",
                 headers,
@@ -302,7 +559,7 @@ mod tests {
         headers.set("Content-Length", "22");
         assert_eq!(
             Ok(Request::new(
-                "HTTP/1.1",
+                Version::Http11,
                 Method::GET,
                 "/path/",
                 headers,
@@ -323,7 +580,7 @@ mod tests {
         headers.set("Test-2", "Value-2");
         assert_eq!(
             Ok(Request::new(
-                "HTTP/1.1",
+                Version::Http11,
                 Method::GET,
                 "/path/",
                 headers,
@@ -344,7 +601,7 @@ mod tests {
         headers.set("Test-2", "Value-2");
         assert_eq!(
             Ok(Request::new(
-                "HTTP/1.1",
+                Version::Http11,
                 Method::GET,
                 "/path/",
                 headers,
@@ -354,6 +611,505 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parser_parse_chunked_body() {
+        let block = "POST /path/ HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n9\r\nDeveloper\r\n5\r\n Test\r\n0\r\n\r\n";
+        let mut parser = ReqParser::new_capacity(4096);
+        assert_eq!((true, None), parser.block_parse(block.as_bytes()));
+
+        let mut headers = Headers::new();
+        headers.set("Transfer-Encoding", "chunked");
+        assert_eq!(
+            Ok(Request::new(
+                Version::Http11,
+                Method::POST,
+                "/path/",
+                headers,
+                "Developer Test".as_bytes()
+            )),
+            parser.finish()
+        );
+    }
+    #[test]
+    fn parser_parse_chunked_body_multiple_blocks() {
+        let mut parser = ReqParser::new_capacity(4096);
+        assert_eq!(
+            (false, None),
+            parser.block_parse(
+                "POST /path/ HTTP/1.1\r\nTransfer-Encoding: gzip, chunked\r\n\r\n9\r\nDevel"
+                    .as_bytes()
+            )
+        );
+        assert_eq!(
+            (false, None),
+            parser.block_parse("oper\r\n5\r\n Test\r\n".as_bytes())
+        );
+        assert_eq!(
+            (true, Some(3)),
+            parser.block_parse("0\r\n\r\nGET".as_bytes())
+        );
+
+        let mut headers = Headers::new();
+        headers.set("Transfer-Encoding", "gzip, chunked");
+        assert_eq!(
+            Ok(Request::new(
+                Version::Http11,
+                Method::POST,
+                "/path/",
+                headers,
+                "Developer Test".as_bytes()
+            )),
+            parser.finish()
+        );
+    }
+    /// Known Request-Smuggling Payloads, together with the Error
+    /// the Parser is expected to reject them with
+    const SMUGGLING_CORPUS: &[(&str, ParseError)] = &[
+        // CL.TE
+        (
+            "POST / HTTP/1.1\r\nHost: a\r\nContent-Length: 13\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\nSMUGGLED",
+            ParseError::ContentLengthWithTransferEncoding,
+        ),
+        // TE.CL
+        (
+            "POST / HTTP/1.1\r\nHost: a\r\nTransfer-Encoding: chunked\r\nContent-Length: 3\r\n\r\n8\r\nSMUGGLED\r\n0\r\n\r\n",
+            ParseError::ContentLengthWithTransferEncoding,
+        ),
+        // TE.TE with an obfuscated second Header
+        (
+            "POST / HTTP/1.1\r\nHost: a\r\nTransfer-Encoding: chunked\r\nTransfer-encoding: cow\r\n\r\n0\r\n\r\n",
+            ParseError::DuplicateFramingHeader,
+        ),
+        (
+            "POST / HTTP/1.1\r\nHost: a\r\nTransfer-Encoding: chunked\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\n",
+            ParseError::DuplicateFramingHeader,
+        ),
+        (
+            "POST / HTTP/1.1\r\nHost: a\r\nContent-Length: 5\r\nContent-Length: 5\r\n\r\nhello",
+            ParseError::DuplicateFramingHeader,
+        ),
+        (
+            "POST / HTTP/1.1\r\nHost: a\r\nContent-Length: 5\r\ncontent-length: 6\r\n\r\nhello!",
+            ParseError::DuplicateFramingHeader,
+        ),
+        // Whitespace between the Name and the Colon
+        (
+            "POST / HTTP/1.1\r\nHost: a\r\nTransfer-Encoding : chunked\r\n\r\n0\r\n\r\n",
+            ParseError::WhitespaceBeforeColon,
+        ),
+        (
+            "POST / HTTP/1.1\r\nHost: a\r\nTransfer-Encoding\t: chunked\r\n\r\n0\r\n\r\n",
+            ParseError::WhitespaceBeforeColon,
+        ),
+        (
+            "POST / HTTP/1.1\r\nHost: a\r\nContent-Length : 5\r\n\r\nhello",
+            ParseError::WhitespaceBeforeColon,
+        ),
+        // Final Encoding is not chunked
+        (
+            "POST / HTTP/1.1\r\nHost: a\r\nTransfer-Encoding: chunked, identity\r\n\r\n0\r\n\r\n",
+            ParseError::InvalidTransferEncoding,
+        ),
+        (
+            "POST / HTTP/1.1\r\nHost: a\r\nTransfer-Encoding: xchunked\r\n\r\n0\r\n\r\n",
+            ParseError::InvalidTransferEncoding,
+        ),
+        (
+            "POST / HTTP/1.1\r\nHost: a\r\nTransfer-Encoding: chunked, chunked\r\n\r\n0\r\n\r\n",
+            ParseError::InvalidTransferEncoding,
+        ),
+        (
+            "POST / HTTP/1.1\r\nHost: a\r\nTransfer-Encoding: identity\r\n\r\nhello",
+            ParseError::InvalidTransferEncoding,
+        ),
+        // Malformed Content-Length Values
+        (
+            "POST / HTTP/1.1\r\nHost: a\r\nContent-Length: 5, 6\r\n\r\nhello!",
+            ParseError::ConflictingContentLength,
+        ),
+        (
+            "POST / HTTP/1.1\r\nHost: a\r\nContent-Length: +5\r\n\r\nhello",
+            ParseError::InvalidContentLength,
+        ),
+        (
+            "POST / HTTP/1.1\r\nHost: a\r\nContent-Length: 0x5\r\n\r\nhello",
+            ParseError::InvalidContentLength,
+        ),
+        (
+            "POST / HTTP/1.1\r\nHost: a\r\nContent-Length: 18446744073709551621\r\n\r\nhello",
+            ParseError::InvalidContentLength,
+        ),
+    ];
+
+    #[test]
+    fn parser_smuggling_corpus() {
+        for (payload, expected) in SMUGGLING_CORPUS.iter() {
+            let mut parser = ReqParser::new_capacity(4096);
+            let (done, _) = parser.block_parse(payload.as_bytes());
+
+            assert!(done, "Not done parsing {:?}", payload);
+            assert_eq!(Err(expected.clone()), parser.finish(), "{:?}", payload);
+        }
+    }
+
+    #[test]
+    fn parser_limit_request_line() {
+        let mut config = ParserConfig::default();
+        config.limits.max_request_line_length = 16;
+
+        let block = "GET /some/very/long/path HTTP/1.1\r\n\r\n";
+        let mut parser = ReqParser::new_with_config(4096, config);
+        assert_eq!((true, Some(20)), parser.block_parse(block.as_bytes()));
+        assert_eq!(Err(ParseError::RequestLineTooLong), parser.finish());
+    }
+    #[test]
+    fn parser_limit_header_count() {
+        let mut config = ParserConfig::default();
+        config.limits.max_header_count = 1;
+
+        let block = "GET /path/ HTTP/1.1\r\nTest-1: Value-1\r\nTest-2: Value-2\r\n\r\n";
+        let mut parser = ReqParser::new_with_config(4096, config);
+        assert_eq!((true, Some(2)), parser.block_parse(block.as_bytes()));
+        assert_eq!(Err(ParseError::TooManyHeaders), parser.finish());
+    }
+    #[test]
+    fn parser_limit_header_size() {
+        let mut config = ParserConfig::default();
+        config.limits.max_header_size = 16;
+
+        let block = "GET /path/ HTTP/1.1\r\nTest-1: Some longer value\r\n\r\n";
+        let mut parser = ReqParser::new_with_config(4096, config);
+        assert_eq!((true, Some(12)), parser.block_parse(block.as_bytes()));
+        assert_eq!(Err(ParseError::HeaderTooLarge), parser.finish());
+    }
+    #[test]
+    fn parser_limit_head_size() {
+        let mut config = ParserConfig::default();
+        config.limits.max_head_size = 32;
+
+        let block = "GET /path/ HTTP/1.1\r\nTest-1: Value-1\r\nTest-2: Value-2\r\n\r\n";
+        let mut parser = ReqParser::new_with_config(4096, config);
+        assert_eq!((true, Some(24)), parser.block_parse(block.as_bytes()));
+        assert_eq!(Err(ParseError::HeadTooLarge), parser.finish());
+    }
+    #[test]
+    fn parser_limit_body_size() {
+        let mut config = ParserConfig::default();
+        config.limits.max_body_size = 16;
+
+        let block = "GET /path/ HTTP/1.1\r\nContent-Length: 22\r\n\r\nThis is just some body";
+        let mut parser = ReqParser::new_with_config(4096, config);
+        assert_eq!((true, Some(22)), parser.block_parse(block.as_bytes()));
+        assert_eq!(Err(ParseError::BodyTooLarge), parser.finish());
+    }
+    #[test]
+    fn parser_limit_chunked_body_size() {
+        let mut config = ParserConfig::default();
+        config.limits.max_body_size = 16;
+
+        let block = "POST /path/ HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n9\r\nDeveloper\r\n9\r\nDeveloper\r\n0\r\n\r\n";
+        let mut parser = ReqParser::new_with_config(4096, config);
+        assert_eq!((true, Some(5)), parser.block_parse(block.as_bytes()));
+        assert_eq!(Err(ParseError::BodyTooLarge), parser.finish());
+    }
+
+    #[test]
+    fn parser_invalid_content_length() {
+        let block = "GET /path/ HTTP/1.1\r\nContent-Length: abc\r\n\r\n";
+        let mut parser = ReqParser::new_capacity(4096);
+        assert_eq!((true, None), parser.block_parse(block.as_bytes()));
+        assert_eq!(Err(ParseError::InvalidContentLength), parser.finish());
+    }
+    #[test]
+    fn parser_content_length_list() {
+        let block = "GET /path/ HTTP/1.1\r\nContent-Length: 4, 4\r\n\r\nbody";
+        let mut parser = ReqParser::new_capacity(4096);
+        assert_eq!((true, None), parser.block_parse(block.as_bytes()));
+        assert_eq!("body".as_bytes(), parser.finish().unwrap().body());
+    }
+    #[test]
+    fn parser_conflicting_content_length() {
+        let block = "GET /path/ HTTP/1.1\r\nContent-Length: 4, 5\r\n\r\nbody";
+        let mut parser = ReqParser::new_capacity(4096);
+        assert_eq!((true, Some(4)), parser.block_parse(block.as_bytes()));
+        assert_eq!(Err(ParseError::ConflictingContentLength), parser.finish());
+    }
+
+    #[test]
+    fn parser_invalid_encoding_path() {
+        let block = b"GET /pa\xffth/ HTTP/1.1\r\nTest-1: Value-1\r\n\r\n";
+        let mut parser = ReqParser::new_capacity(4096);
+        assert_eq!((true, None), parser.block_parse(block));
+        assert_eq!(Err(ParseError::InvalidEncoding), parser.finish());
+    }
+    #[test]
+    fn parser_invalid_encoding_header() {
+        let block = b"GET /path/ HTTP/1.1\r\nTest-1: Val\xc3\x28ue-1\r\n\r\n";
+        let mut parser = ReqParser::new_capacity(4096);
+        assert_eq!((true, None), parser.block_parse(block));
+
+        let request = parser.finish().unwrap();
+        assert_eq!(
+            Some(&HeaderValue::BytesRef(b"Val\xc3\x28ue-1")),
+            request.headers().get("Test-1")
+        );
+        assert_eq!(block.to_vec(), request.serialize().0);
+    }
+
+    #[test]
+    fn parser_header_without_whitespace() {
+        let block = "GET /path/ HTTP/1.1\r\nTest-1:Value-1\r\nTest-2:\t Value-2\t \r\n\r\n";
+        let mut parser = ReqParser::new_capacity(4096);
+        assert_eq!((true, None), parser.block_parse(block.as_bytes()));
+
+        let request = parser.finish().unwrap();
+        assert_eq!(Version::Http11, request.version());
+        assert_eq!(&Method::GET, request.method());
+        assert_eq!("/path/", request.path());
+        assert_eq!(2, request.headers().get_header_count());
+        assert_eq!(
+            Some(&HeaderValue::StrRef("Value-1")),
+            request.headers().get("Test-1")
+        );
+        assert_eq!(
+            Some(&HeaderValue::StrRef("Value-2")),
+            request.headers().get("Test-2")
+        );
+        assert_eq!("".as_bytes(), request.body());
+    }
+    #[test]
+    fn parser_invalid_header_name() {
+        let cases: &[&[u8]] = &[
+            b"GET /path/ HTTP/1.1\r\n: Value-1\r\n\r\n",
+            b"GET /path/ HTTP/1.1\r\nTest(1): Value-1\r\n\r\n",
+            b"GET /path/ HTTP/1.1\r\nTest 1: Value-1\r\n\r\n",
+            b"GET /path/ HTTP/1.1\r\nTest-\xc3\xa4: Value-1\r\n\r\n",
+        ];
+
+        for block in cases {
+            let mut parser = ReqParser::new_capacity(4096);
+            assert!(parser.block_parse(block).0);
+            assert_eq!(Err(ParseError::InvalidHeaderName), parser.finish());
+        }
+    }
+    #[test]
+    fn parser_invalid_header_value() {
+        let cases: &[&[u8]] = &[
+            b"GET /path/ HTTP/1.1\r\nTest-1: Val\0ue\r\n\r\n",
+            b"GET /path/ HTTP/1.1\r\nTest-1: Val\x1bue\r\n\r\n",
+            b"GET /path/ HTTP/1.1\r\nTest-1: Value\x7f\r\n\r\n",
+        ];
+
+        for block in cases {
+            let mut parser = ReqParser::new_capacity(4096);
+            assert_eq!((true, Some(2)), parser.block_parse(block));
+            assert_eq!(Err(ParseError::InvalidHeaderValue), parser.finish());
+        }
+    }
+    #[test]
+    fn parser_version() {
+        let block = "GET /path/ HTTP/1.0\r\n\r\n";
+        let mut parser = ReqParser::new_capacity(4096);
+        assert_eq!((true, None), parser.block_parse(block.as_bytes()));
+        assert_eq!(Version::Http10, parser.finish().unwrap().version());
+
+        let block = "GET /path/ HTTP/2.0\r\n\r\n";
+        let mut parser = ReqParser::new_capacity(4096);
+        assert_eq!((true, None), parser.block_parse(block.as_bytes()));
+        assert_eq!(Version::Other(2, 0), parser.finish().unwrap().version());
+
+        let block = "GET /path/ HXXP/9\r\n\r\n";
+        let mut parser = ReqParser::new_capacity(4096);
+        assert_eq!((true, Some(2)), parser.block_parse(block.as_bytes()));
+        assert_eq!(Err(ParseError::InvalidVersion), parser.finish());
+    }
+    #[test]
+    fn parser_request_target() {
+        let block = "CONNECT example.com:443 HTTP/1.1\r\n\r\n";
+        let mut parser = ReqParser::new_capacity(4096);
+        assert_eq!((true, None), parser.block_parse(block.as_bytes()));
+        let req = parser.finish().unwrap();
+        let target = req.target().unwrap();
+        assert_eq!(TargetForm::Authority, target.form());
+        assert_eq!(Some("example.com:443"), target.authority());
+
+        let block = "OPTIONS * HTTP/1.1\r\n\r\n";
+        let mut parser = ReqParser::new_capacity(4096);
+        assert_eq!((true, None), parser.block_parse(block.as_bytes()));
+        assert!(parser.finish().is_ok());
+    }
+    #[test]
+    fn parser_request_target_mismatch() {
+        let cases = &[
+            "GET example.com:443 HTTP/1.1\r\n\r\n",
+            "CONNECT /path/ HTTP/1.1\r\n\r\n",
+            "GET * HTTP/1.1\r\n\r\n",
+        ];
+
+        for block in cases {
+            let mut parser = ReqParser::new_capacity(4096);
+            assert_eq!((true, None), parser.block_parse(block.as_bytes()));
+            assert_eq!(Err(ParseError::TargetFormMismatch), parser.finish());
+        }
+    }
+    #[test]
+    fn parser_validate_host() {
+        let config = ParserConfig {
+            validate_host: true,
+            ..ParserConfig::default()
+        };
+        let cases: &[(&str, Option<ParseError>)] = &[
+            ("GET / HTTP/1.1\r\nHost: example.com\r\n\r\n", None),
+            ("GET / HTTP/1.1\r\n\r\n", Some(ParseError::MissingHost)),
+            (
+                "GET / HTTP/1.1\r\nHost: a.com\r\nHost: b.com\r\n\r\n",
+                Some(ParseError::DuplicateHost),
+            ),
+            (
+                "GET http://a.com/ HTTP/1.1\r\nHost: b.com\r\n\r\n",
+                Some(ParseError::HostMismatch),
+            ),
+            (
+                "GET / HTTP/1.1\r\nHost: a.com:x\r\n\r\n",
+                Some(ParseError::InvalidHost),
+            ),
+        ];
+
+        for (block, error) in cases {
+            let mut parser = ReqParser::new_with_config(4096, config.clone());
+            assert_eq!((true, None), parser.block_parse(block.as_bytes()));
+            assert_eq!(
+                error.as_ref(),
+                parser.finish().err().as_ref(),
+                "{:?}",
+                block
+            );
+        }
+    }
+    #[test]
+    fn parser_strict_profile() {
+        let cases: &[(&str, ParseError)] = &[
+            (
+                "GET /path/ HTTP/1.1\nHost: a\n\n",
+                ParseError::InvalidLineEnding,
+            ),
+            (
+                "GET /path/ HTTP/1.1\r\nHost: a\rb\r\n\r\n",
+                ParseError::InvalidLineEnding,
+            ),
+            (
+                "\r\nGET /path/ HTTP/1.1\r\n\r\n",
+                ParseError::LeadingEmptyLine,
+            ),
+            (
+                "GET  /path/ HTTP/1.1\r\n\r\n",
+                ParseError::InvalidWhitespace,
+            ),
+            (
+                "GET\t/path/ HTTP/1.1\r\n\r\n",
+                ParseError::InvalidWhitespace,
+            ),
+            (
+                "GET /path/ HTTP/1.1 \r\n\r\n",
+                ParseError::InvalidWhitespace,
+            ),
+            (
+                "GET /path/ HTTP/1.1\r\nTest-1: Value-1\r\n  continued\r\n\r\n",
+                ParseError::ObsoleteLineFolding,
+            ),
+            (
+                "GET /path/ HTTP/1.1\r\nno colon\r\n\r\n",
+                ParseError::InvalidHeader,
+            ),
+        ];
+
+        for (block, error) in cases {
+            let mut parser = ReqParser::new_capacity(4096);
+            let (done, _) = parser.block_parse(block.as_bytes());
+            assert!(done, "{:?}", block);
+            assert_eq!(Err(error.clone()), parser.finish(), "{:?}", block);
+        }
+    }
+    #[test]
+    fn parser_lenient_profile() {
+        let config = ParserConfig {
+            profile: ParseProfile::Lenient,
+            ..ParserConfig::default()
+        };
+        let block =
+            "\r\n\nGET \t/path/  HTTP/1.1 \nTest-1:\tValue-1\r\n\tcontinued \nTest-2: Value-2\n\n";
+
+        let mut parser = ReqParser::new_with_config(4096, config);
+        assert_eq!((true, None), parser.block_parse(block.as_bytes()));
+
+        let request = parser.finish().unwrap();
+        assert_eq!(Version::Http11, request.version());
+        assert_eq!(&Method::GET, request.method());
+        assert_eq!("/path/", request.path());
+        assert_eq!(2, request.headers().get_header_count());
+        assert_eq!(
+            Some(&HeaderValue::StrRef("Value-1   continued")),
+            request.headers().get("Test-1")
+        );
+        assert_eq!(
+            Some(&HeaderValue::StrRef("Value-2")),
+            request.headers().get("Test-2")
+        );
+        assert_eq!("".as_bytes(), request.body());
+    }
+    #[test]
+    fn parser_lenient_profile_chunked() {
+        let config = ParserConfig {
+            profile: ParseProfile::Lenient,
+            ..ParserConfig::default()
+        };
+        let block = "POST /path/ HTTP/1.1\nTransfer-Encoding: chunked\n\n4\nTest\n0\n\n";
+
+        let mut parser = ReqParser::new_with_config(4096, config);
+        assert_eq!((true, None), parser.block_parse(block.as_bytes()));
+
+        let request = parser.finish().unwrap();
+        assert_eq!(&Method::POST, request.method());
+        assert_eq!("/path/", request.path());
+        assert_eq!(
+            Some(&HeaderValue::StrRef("chunked")),
+            request.headers().get("Transfer-Encoding")
+        );
+        assert_eq!("Test".as_bytes(), request.body());
+    }
+    #[test]
+    fn parser_extension_method() {
+        let block = "PURGE /path/ HTTP/1.1\r\n\r\n";
+
+        let mut parser = ReqParser::new_capacity(4096);
+        assert_eq!((true, None), parser.block_parse(block.as_bytes()));
+        assert_eq!(
+            &Method::Extension("PURGE".to_owned()),
+            parser.finish().unwrap().method()
+        );
+    }
+    #[test]
+    fn parser_standard_headers() {
+        let block = "GET /path/ HTTP/1.1\r\nHost: example.com\r\naccept: */*\r\n\r\n";
+
+        let mut parser = ReqParser::new_capacity(4096);
+        assert_eq!((true, None), parser.block_parse(block.as_bytes()));
+        let request = parser.finish().unwrap();
+        let keys: Vec<&HeaderKey> = request.headers().iter().map(|h| &h.key).collect();
+        assert!(matches!(
+            keys[0],
+            HeaderKey::Standard(header::StandardHeader::Host)
+        ));
+        assert!(matches!(keys[1], HeaderKey::StrRef("accept")));
+    }
+    #[test]
+    fn parser_invalid_method() {
+        let block = "GE(T /path/ HTTP/1.1\r\n\r\n";
+
+        let mut parser = ReqParser::new_capacity(4096);
+        assert_eq!((true, None), parser.block_parse(block.as_bytes()));
+        assert_eq!(Err(ParseError::MissingMethod), parser.finish());
+    }
     #[test]
     fn parser_missing_method() {
         let block = "";
@@ -395,7 +1151,8 @@ mod tests {
         ];
         let mut parser = ReqParser::new_capacity(2048);
 
-        assert_eq!((false, None), parser.block_parse(&block));
+        assert_eq!((true, Some(23)), parser.block_parse(&block));
+        assert_eq!(Err(ParseError::InvalidLineEnding), parser.finish());
     }
     #[test]
     fn parser_fuzzing_bug_1() {
@@ -407,8 +1164,151 @@ mod tests {
         ];
         let mut parser = ReqParser::new_capacity(2048);
 
-        assert_eq!((true, Some(1)), parser.block_parse(&block));
-        // This is somehow a valid request according to my parser
-        assert_eq!(true, parser.finish().is_ok());
+        assert_eq!((true, Some(83)), parser.block_parse(&block));
+        assert_eq!(Err(ParseError::InvalidWhitespace), parser.finish());
+    }
+
+    #[test]
+    fn parser_preserve_raw() {
+        let config = ParserConfig {
+            profile: ParseProfile::Lenient,
+            preserve_raw: true,
+            ..ParserConfig::default()
+        };
+        let head =
+            "GET  /path HTTP/1.1\r\nhost:example.com\r\nX-Custom:   Value  \nACCEPT: */*\r\n\r\n";
+
+        let mut parser = ReqParser::new_with_config(1024, config);
+        assert_eq!((true, None), parser.block_parse(head.as_bytes()));
+        let mut request = parser.finish().unwrap();
+
+        let raw = request.raw_head().unwrap().clone();
+        assert_eq!(head.as_bytes(), raw.bytes());
+        assert_eq!(b"/path", raw.get(raw.start_line_parts()[1]));
+        assert_eq!(b"X-Custom:   Value  \n", raw.get(raw.headers()[1].line));
+        assert_eq!(b"Value", raw.get(raw.headers()[1].value));
+        assert_eq!(head.as_bytes(), &request.serialize().0);
+
+        request.header_mut().set("x-custom", "Changed");
+        assert_eq!(
+            "GET  /path HTTP/1.1\r\nhost:example.com\r\nACCEPT: */*\r\nx-custom: Changed\r\n\r\n"
+                .as_bytes(),
+            &request.serialize().0
+        );
+
+        request.set_path_ref("/other");
+        assert_eq!(
+            "GET /other HTTP/1.1\r\nhost:example.com\r\nACCEPT: */*\r\nx-custom: Changed\r\n\r\n"
+                .as_bytes(),
+            &request.serialize().0
+        );
+    }
+    #[test]
+    fn parser_preserve_raw_folded() {
+        let config = ParserConfig {
+            profile: ParseProfile::Lenient,
+            preserve_raw: true,
+            ..ParserConfig::default()
+        };
+        let head =
+            "GET /path HTTP/1.1\r\nTest-1: Value-1\r\n\tcontinued\r\nTest-2: Value-2\r\n\r\n";
+
+        let mut parser = ReqParser::new_with_config(1024, config);
+        assert_eq!((true, None), parser.block_parse(head.as_bytes()));
+        let request = parser.finish().unwrap();
+
+        let raw = request.raw_head().unwrap();
+        assert_eq!(head.as_bytes(), raw.bytes());
+        assert_eq!(
+            b"Test-1: Value-1\r\n\tcontinued\r\n",
+            raw.get(raw.headers()[0].line)
+        );
+        assert_eq!(
+            Some(&HeaderValue::StrRef("Value-1   continued")),
+            request.headers().get("Test-1")
+        );
+        assert_eq!(
+            "GET /path HTTP/1.1\r\nTest-1: Value-1   continued\r\nTest-2: Value-2\r\n\r\n"
+                .as_bytes(),
+            &request.serialize().0
+        );
+
+        // The Buffer is reused for the next Request
+        parser.clear();
+        let head = "GET /path HTTP/1.1\r\nTest-1: Value-1\r\n\r\n";
+        assert_eq!((true, None), parser.block_parse(head.as_bytes()));
+        let request = parser.finish().unwrap();
+        assert_eq!(head.as_bytes(), request.raw_head().unwrap().bytes());
+    }
+    #[test]
+    fn parser_preserve_raw_multiple_folds() {
+        let config = ParserConfig {
+            profile: ParseProfile::Lenient,
+            preserve_raw: true,
+            ..ParserConfig::default()
+        };
+        let head = "GET / HTTP/1.1\r\nX-A: one\r\n   \r\n two\r\n\r\n";
+
+        let mut parser = ReqParser::new_with_config(1024, config);
+        assert_eq!((true, None), parser.block_parse(head.as_bytes()));
+        let request = parser.finish().unwrap();
+
+        let raw = request.raw_head().unwrap();
+        assert_eq!(head.as_bytes(), raw.bytes());
+        assert_eq!(
+            b"X-A: one\r\n   \r\n two\r\n",
+            raw.get(raw.headers()[0].line)
+        );
+        assert_eq!(
+            Some(&HeaderValue::StrRef("one        two")),
+            request.headers().get("X-A")
+        );
+    }
+    #[test]
+    fn parser_without_raw() {
+        let mut parser = ReqParser::new_capacity(1024);
+        assert_eq!(
+            (true, None),
+            parser.block_parse("GET / HTTP/1.1\r\nhost:example.com\r\n\r\n".as_bytes())
+        );
+        let request = parser.finish().unwrap();
+
+        assert_eq!(None, request.raw_head());
+        assert_eq!(
+            "GET / HTTP/1.1\r\nhost: example.com\r\n\r\n".as_bytes(),
+            &request.serialize().0
+        );
+    }
+
+    #[test]
+    fn parser_duplicate_headers() {
+        // Every Header is kept by default
+        let block = "GET / HTTP/1.1\r\nContent-Type: a\r\ncontent-type: b\r\n\r\n";
+        let mut parser = ReqParser::new_capacity(1024);
+        assert_eq!((true, None), parser.block_parse(block.as_bytes()));
+        assert_eq!(2, parser.finish().unwrap().headers().get_header_count());
+
+        let config = ParserConfig {
+            duplicates: DuplicatePolicy::RejectSingletons,
+            ..ParserConfig::default()
+        };
+        let mut parser = ReqParser::new_with_config(1024, config);
+        assert_eq!((true, None), parser.block_parse(block.as_bytes()));
+        assert_eq!(Err(ParseError::DuplicateHeader), parser.finish());
+
+        let config = ParserConfig {
+            duplicates: DuplicatePolicy::MergeLists,
+            ..ParserConfig::default()
+        };
+        let block = "GET / HTTP/1.1\r\nAccept: a\r\nCookie: b\r\naccept: c\r\n\r\n";
+        let mut parser = ReqParser::new_with_config(1024, config);
+        assert_eq!((true, None), parser.block_parse(block.as_bytes()));
+
+        let request = parser.finish().unwrap();
+        assert_eq!(2, request.headers().get_header_count());
+        assert_eq!(
+            Some(&HeaderValue::Str("a, c".to_owned())),
+            request.headers().get("Accept")
+        );
     }
 }
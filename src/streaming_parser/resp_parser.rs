@@ -1,16 +1,26 @@
-use crate::streaming_parser::ParseError;
-use crate::{header::HeaderKey, Headers, Response, StatusCode};
+use crate::streaming_parser::{
+    duplicates, framing, ChunkParser, ParseError, ParseProfile, ParseResult, ParserConfig,
+};
+use crate::{
+    grammar,
+    header::{self, HeaderKey, HeaderValue},
+    inline_vec::InlineVec,
+    raw, Headers, RawHead, Response, Span, StatusCode, Version,
+};
 
 type ProtocolState = (usize, usize);
 type StatusCodeState = (usize, usize);
 type HeaderKeyState = (usize, usize);
 
 enum ParseState {
-    Nothing,
-    ProtocolParsed(ProtocolState),
+    /// The Protocol starts at the given Position
+    Nothing(usize),
+    /// The StatusCode starts at the given Position
+    ProtocolParsed(ProtocolState, usize),
+    /// The current Header-Line starts at the given Position
     HeaderKey(ProtocolState, StatusCodeState, usize),
     HeaderValue(ProtocolState, StatusCodeState, HeaderKeyState),
-    HeadersParsed(ProtocolState, StatusCodeState, usize),
+    HeadersParsed(ProtocolState, StatusCodeState),
 }
 
 #[derive(Debug)]
@@ -18,6 +28,10 @@ enum ProgressState {
     Head,
     /// The Length the Body is expected to be
     Body(usize),
+    /// The Body is send using `Transfer-Encoding: chunked`
+    Chunked,
+    /// The Body ends once the Server closes the Connection
+    UntilClose,
     Done,
 }
 
@@ -26,7 +40,14 @@ enum ProgressState {
 pub struct RespParser {
     buffer: Vec<u8>,
     body_buffer: Vec<u8>,
-    headers_buf: Vec<((usize, usize), (usize, usize))>,
+    headers_buf: InlineVec<((usize, usize), (usize, usize)), 16>,
+    /// The original Bytes of every obsolete Line-Folding that was
+    /// replaced in the Buffer, only kept if the raw Head is preserved
+    folds: Vec<(usize, Vec<u8>)>,
+    chunk_parser: ChunkParser,
+    trailers: Option<Headers<'static>>,
+    error: Option<ParseError>,
+    config: ParserConfig,
     state: ParseState,
     progress: ProgressState,
 }
@@ -34,13 +55,27 @@ pub struct RespParser {
 impl RespParser {
     /// Creates a new Instance of the Response-Parser
     /// with the given `head_cap` as its initial capacity
-    /// for the HTTP-Head (First-Line + Headers)
+    /// for the HTTP-Head (First-Line + Headers), using the
+    /// default Configuration
     pub fn new_capacity(head_cap: usize) -> Self {
+        Self::new_with_config(head_cap, ParserConfig::default())
+    }
+
+    /// Creates a new Instance of the Response-Parser
+    /// with the given `head_cap` as its initial capacity
+    /// for the HTTP-Head (First-Line + Headers), that uses
+    /// the given Configuration
+    pub fn new_with_config(head_cap: usize, config: ParserConfig) -> Self {
         Self {
             buffer: Vec::with_capacity(head_cap),
             body_buffer: Vec::new(),
-            headers_buf: Vec::with_capacity(20),
-            state: ParseState::Nothing,
+            headers_buf: InlineVec::new(),
+            folds: Vec::new(),
+            chunk_parser: ChunkParser::new_with_config(config.clone()),
+            trailers: None,
+            error: None,
+            config,
+            state: ParseState::Nothing(0),
             progress: ProgressState::Head,
         }
     }
@@ -56,88 +91,241 @@ impl RespParser {
         self.buffer.clear();
         self.body_buffer.clear();
         self.headers_buf.clear();
+        self.folds.clear();
+        self.chunk_parser.clear();
+        self.trailers = None;
+        self.error = None;
 
         // Reset internal State to the beginning
-        self.state = ParseState::Nothing;
+        self.state = ParseState::Nothing(0);
         self.progress = ProgressState::Head;
     }
 
-    #[inline(always)]
-    fn parse(&mut self, byte: u8, current: usize) -> ProgressState {
-        match &mut self.state {
-            ParseState::Nothing if byte == b' ' => {
-                let end = current;
-                self.state = ParseState::ProtocolParsed((0, end));
-                ProgressState::Head
-            }
-            ParseState::ProtocolParsed(protocol) if byte == b'\r' => {
-                let start = protocol.1;
-                let end = current;
+    /// Checks if the current Byte would exceed any of the
+    /// configured Limits
+    fn check_limits(&self, current: usize) -> ParseResult<()> {
+        let limits = &self.config.limits;
+        if current >= limits.max_head_size {
+            return Err(ParseError::HeadTooLarge);
+        }
 
-                self.state = ParseState::HeaderKey(*protocol, (start + 1, end), end);
-                ProgressState::Head
+        match &self.state {
+            ParseState::Nothing(start) | ParseState::ProtocolParsed((start, _), _)
+                if current >= *start + limits.max_request_line_length =>
+            {
+                Err(ParseError::RequestLineTooLong)
             }
-            ParseState::HeaderKey(protocol, status_code, raw_start)
-                if current == *raw_start + 2 && byte == b'\r' =>
+            ParseState::HeaderKey(_, _, line_start)
+                if current >= *line_start + limits.max_header_size =>
             {
-                self.state = ParseState::HeadersParsed(*protocol, *status_code, current + 2);
-                ProgressState::Head
+                Err(ParseError::HeaderTooLarge)
             }
-            ParseState::HeaderKey(protocol, status_code, raw_start)
-                if byte == b':' && *raw_start + 2 <= current =>
+            ParseState::HeaderValue(_, _, header_key)
+                if current >= header_key.0 + limits.max_header_size =>
             {
-                let start = *raw_start + 2;
-                let end = current;
+                Err(ParseError::HeaderTooLarge)
+            }
+            _ => Ok(()),
+        }
+    }
 
-                self.state = ParseState::HeaderValue(*protocol, *status_code, (start, end));
-                ProgressState::Head
+    #[inline(always)]
+    fn parse(&mut self, byte: u8, current: usize) -> ProgressState {
+        match self.parse_byte(byte, current) {
+            Ok(progress) => progress,
+            Err(e) => {
+                self.error = Some(e);
+                ProgressState::Done
             }
-            ParseState::HeaderValue(protocol, status_code, header_key)
-                if byte == b'\r' && header_key.1 + 2 <= current =>
-            {
-                let start = header_key.1 + 2;
-                let end = current;
+        }
+    }
 
-                self.headers_buf.push((*header_key, (start, end)));
-                self.state = ParseState::HeaderKey(*protocol, *status_code, end);
-                ProgressState::Head
+    fn parse_byte(&mut self, byte: u8, current: usize) -> ParseResult<ProgressState> {
+        self.check_limits(current)?;
+
+        let profile = self.config.profile;
+        let line_end = profile.line_end(&self.buffer[..=current])?;
+        let separator = match self.state {
+            ParseState::Nothing(_) => profile.is_separator(byte)?,
+            ParseState::ProtocolParsed(_, status_start) if current == status_start => {
+                profile.is_separator(byte)?
             }
-            ParseState::HeadersParsed(_, _, end) if current == *end - 1 => {
-                // The Length the body is supposed to have
-                let mut length: usize = 0;
-                for raw_header_pair in self.headers_buf.iter() {
-                    let key_pair = raw_header_pair.0;
-                    let value_pair = raw_header_pair.1;
-
-                    let key_str = match std::str::from_utf8(&self.buffer[key_pair.0..key_pair.1]) {
-                        Ok(k) => k,
-                        Err(_) => {
-                            continue;
-                        }
-                    };
-                    if HeaderKey::StrRef(key_str) != HeaderKey::StrRef("Content-Length") {
-                        continue;
+            _ => false,
+        };
+
+        match &mut self.state {
+            ParseState::Nothing(start) => match line_end {
+                Some(end) if end == *start => {
+                    if profile == ParseProfile::Strict {
+                        return Err(ParseError::LeadingEmptyLine);
+                    }
+                    *start = current + 1;
+                }
+                Some(_) => return Err(ParseError::MissingStatusCode),
+                None if separator => {
+                    if current == *start {
+                        return Err(ParseError::MissingProtocol);
+                    }
+                    if Version::parse_bytes(&self.buffer[*start..current]).is_none() {
+                        return Err(ParseError::InvalidVersion);
+                    }
+                    self.state = ParseState::ProtocolParsed((*start, current), current + 1);
+                }
+                None => {}
+            },
+            ParseState::ProtocolParsed(protocol, status_start) => match line_end {
+                Some(mut end) => {
+                    // The Reason-Phrase may contain Spaces, so only trailing
+                    // Whitespace is removed in Lenient-Mode
+                    if profile == ParseProfile::Lenient {
+                        end = grammar::trim_whitespace_range(&self.buffer, (*status_start, end)).1;
+                    }
+
+                    self.state =
+                        ParseState::HeaderKey(*protocol, (*status_start, end), current + 1);
+                }
+                None if separator => {
+                    if profile == ParseProfile::Strict {
+                        return Err(ParseError::InvalidWhitespace);
+                    }
+                    *status_start = current + 1;
+                }
+                None => {}
+            },
+            ParseState::HeaderKey(protocol, status_code, line_start) => {
+                if let Some(end) = line_end {
+                    if end != *line_start {
+                        return Err(ParseError::InvalidHeader);
+                    }
+
+                    // The empty Line marks the End of the Head
+                    self.state = ParseState::HeadersParsed(*protocol, *status_code);
+                    return self.framing();
+                }
+
+                if current == *line_start && grammar::is_whitespace(byte) {
+                    // Obsolete Line-Folding, as described in RFC 9112 5.2
+                    if profile == ParseProfile::Strict {
+                        return Err(ParseError::ObsoleteLineFolding);
                     }
+                    let (key, value) = self.headers_buf.pop().ok_or(ParseError::InvalidHeader)?;
 
-                    let value_str =
-                        match std::str::from_utf8(&self.buffer[value_pair.0..value_pair.1]) {
-                            Ok(v) => v,
-                            Err(_) => {
-                                continue;
-                            }
+                    // Replaces the Line-Break and the Whitespace that starts the
+                    // Continuation with Spaces to continue the previous Value
+                    if self.config.preserve_raw {
+                        // An earlier Continuation of the same Value may already be
+                        // replaced, so only the Bytes after it are still the original ones
+                        let start = match self.folds.last() {
+                            Some((start, original)) => value.1.max(start + original.len()),
+                            None => value.1,
                         };
+                        self.folds
+                            .push((start, self.buffer[start..=current].to_vec()));
+                    }
+                    for tmp in self.buffer[value.1..=current].iter_mut() {
+                        *tmp = b' ';
+                    }
+                    self.state = ParseState::HeaderValue(*protocol, *status_code, key);
+                } else if byte == b':' {
+                    if !grammar::is_token(&self.buffer[*line_start..current]) {
+                        return Err(ParseError::InvalidHeaderName);
+                    }
 
-                    length = value_str.parse().unwrap();
-                    break;
+                    self.state =
+                        ParseState::HeaderValue(*protocol, *status_code, (*line_start, current));
                 }
+            }
+            ParseState::HeaderValue(protocol, status_code, header_key) => {
+                if let Some(end) = line_end {
+                    let value =
+                        grammar::trim_whitespace_range(&self.buffer, (header_key.1 + 1, end));
+                    if !grammar::is_field_value(&self.buffer[value.0..value.1]) {
+                        return Err(ParseError::InvalidHeaderValue);
+                    }
 
-                if length > 0 {
-                    ProgressState::Body(length)
-                } else {
-                    ProgressState::Done
+                    if self.headers_buf.len() >= self.config.limits.max_header_count {
+                        return Err(ParseError::TooManyHeaders);
+                    }
+
+                    self.headers_buf.push((*header_key, value));
+                    self.state = ParseState::HeaderKey(*protocol, *status_code, current + 1);
                 }
             }
-            _ => ProgressState::Head,
+            ParseState::HeadersParsed(_, _) => return Ok(ProgressState::Done),
+        };
+
+        Ok(ProgressState::Head)
+    }
+
+    /// Determines how the Body of the Response is framed, once all
+    /// the Headers have been received
+    fn framing(&self) -> ParseResult<ProgressState> {
+        // The Length the body is supposed to have
+        let mut length: Option<usize> = None;
+        // The last Transfer-Encoding that does not end with chunked
+        let mut transfer_encoding: Option<&[u8]> = None;
+        for raw_header_pair in self.headers_buf.iter() {
+            let key_pair = raw_header_pair.0;
+            let value_pair = raw_header_pair.1;
+
+            let key_str = match std::str::from_utf8(&self.buffer[key_pair.0..key_pair.1]) {
+                Ok(k) => k,
+                Err(_) => {
+                    continue;
+                }
+            };
+            let key = HeaderKey::StrRef(key_str);
+            let raw_value = &self.buffer[value_pair.0..value_pair.1];
+
+            // A chunked Transfer-Encoding always takes precedence over
+            // any Content-Length that may also be present
+            if key == header::TRANSFER_ENCODING {
+                match std::str::from_utf8(raw_value) {
+                    Ok(value_str) if framing::is_chunked(value_str) => {
+                        return Ok(ProgressState::Chunked);
+                    }
+                    _ => {
+                        transfer_encoding = Some(raw_value);
+                        continue;
+                    }
+                };
+            }
+            if key != header::CONTENT_LENGTH {
+                continue;
+            }
+
+            let parsed = framing::parse_content_length(raw_value)?;
+            match length {
+                Some(previous) if previous != parsed => {
+                    return Err(ParseError::ConflictingContentLength);
+                }
+                _ => length = Some(parsed),
+            };
+        }
+
+        // A Head without any Transfer-Encoding is treated as complete, even if
+        // it has no Content-Length, so that Responses without a Body can be
+        // parsed without knowing the Request they belong to
+        let status = match &self.state {
+            ParseState::HeadersParsed(_, status) => {
+                std::str::from_utf8(&self.buffer[status.0..status.1])
+                    .ok()
+                    .and_then(StatusCode::parse)
+            }
+            _ => None,
+        };
+        let status = status.map_or(200, |status| status.as_u16());
+        if transfer_encoding.is_some()
+            && framing::reads_until_close(status, transfer_encoding, length.is_some())
+        {
+            return Ok(ProgressState::UntilClose);
+        }
+        match length {
+            Some(length) if length > self.config.limits.max_body_size => {
+                Err(ParseError::BodyTooLarge)
+            }
+            Some(length) if length > 0 => Ok(ProgressState::Body(length)),
+            _ => Ok(ProgressState::Done),
         }
     }
 
@@ -147,7 +335,7 @@ impl RespParser {
     /// * `True` if the parser is done and finish can be called
     /// * `False` if it is not yet done with parsing
     /// * Some when there was still data in the given buffer, which
-    /// was not consumed/used
+    ///   was not consumed/used
     pub fn block_parse(&mut self, bytes: &[u8]) -> (bool, usize) {
         match self.progress {
             ProgressState::Head => {
@@ -162,7 +350,9 @@ impl RespParser {
                             self.body_buffer.reserve(length);
                             return self.block_parse(&bytes[index + 1..]);
                         }
-                        ProgressState::Done => {
+                        ProgressState::Chunked
+                        | ProgressState::UntilClose
+                        | ProgressState::Done => {
                             return self.block_parse(&bytes[index + 1..]);
                         }
                         _ => {}
@@ -170,6 +360,38 @@ impl RespParser {
                 }
                 (false, 0)
             }
+            ProgressState::Chunked => {
+                let (done, left) = self.chunk_parser.block_parse(bytes);
+                if !done {
+                    return (false, 0);
+                }
+                let consumed = bytes.len() - left;
+
+                let chunk = self.chunk_parser.finish();
+                self.chunk_parser.clear();
+                match chunk {
+                    Ok(mut chunk) if chunk.is_last() => {
+                        self.trailers = chunk.take_trailers();
+                        self.progress = ProgressState::Done;
+                    }
+                    Ok(chunk)
+                        if self.body_buffer.len() + chunk.size()
+                            > self.config.limits.max_body_size =>
+                    {
+                        self.error = Some(ParseError::BodyTooLarge);
+                        self.progress = ProgressState::Done;
+                    }
+                    Ok(chunk) => {
+                        self.body_buffer.extend_from_slice(chunk.body());
+                    }
+                    Err(e) => {
+                        self.error = Some(e);
+                        self.progress = ProgressState::Done;
+                    }
+                };
+
+                self.block_parse(&bytes[consumed..])
+            }
             ProgressState::Body(length) => {
                 let left_to_read = length - self.body_buffer.len();
                 if left_to_read == 0 {
@@ -187,22 +409,75 @@ impl RespParser {
                     self.block_parse(&bytes[left_to_read..])
                 }
             }
+            ProgressState::UntilClose => {
+                if self.body_buffer.len() + bytes.len() > self.config.limits.max_body_size {
+                    self.error = Some(ParseError::BodyTooLarge);
+                    self.progress = ProgressState::Done;
+                    return self.block_parse(bytes);
+                }
+
+                self.body_buffer.extend_from_slice(bytes);
+                (false, 0)
+            }
             ProgressState::Done => (true, bytes.len()),
         }
     }
 
+    /// Checks if the Body of the Response is only delimited by the
+    /// Server closing the Connection, in which case the Parser is never
+    /// done and `finish` should be called once the Connection was closed
+    pub fn reads_until_close(&self) -> bool {
+        matches!(self.progress, ProgressState::UntilClose)
+    }
+
+    /// Returns the Trailer-Fields that were send after the
+    /// last Chunk of a chunked Body, if there were any
+    pub fn trailers(&self) -> Option<&Headers<'static>> {
+        self.trailers.as_ref()
+    }
+
+    /// Creates the raw Head for the parsed Response, if the Parser is
+    /// configured to keep it
+    fn raw_head(&self) -> Option<RawHead<'_>> {
+        let (protocol, status_code) = match &self.state {
+            ParseState::HeadersParsed(p, stc) if self.config.preserve_raw => (p, stc),
+            _ => return None,
+        };
+
+        // The Status-Code is always 3 Digits, followed by the optional Reason-Phrase
+        let code_end = (status_code.0 + 3).min(status_code.1);
+        let reason_start = (code_end + 1).min(status_code.1);
+        let spans = [
+            (*protocol).into(),
+            Span::new(status_code.0, code_end),
+            Span::new(reason_start, status_code.1),
+        ];
+
+        Some(RawHead::new(
+            raw::restore_folds(&self.buffer, &self.folds),
+            spans,
+            self.headers_buf
+                .iter()
+                .map(|(key, value)| ((*key).into(), (*value).into())),
+        ))
+    }
+
     /// Finalizes the Response that is currently being Parsed by the Parser
     /// and returns a Response-Instance with the parsed Data
     pub fn finish<'a, 'b>(&'a mut self) -> Result<Response<'b>, ParseError>
     where
         'a: 'b,
     {
+        if let Some(e) = &self.error {
+            return Err(e.clone());
+        }
+
         let (protocol, status_code) = match &self.state {
-            ParseState::HeadersParsed(p, stc, _) => (p, stc),
-            ParseState::Nothing => {
+            ParseState::HeadersParsed(p, stc) => (p, stc),
+            ParseState::Nothing(_) => {
                 return Err(ParseError::MissingProtocol);
             }
-            ParseState::ProtocolParsed(_) => {
+            ParseState::ProtocolParsed(_, _) => {
                 return Err(ParseError::MissingStatusCode);
             }
             ParseState::HeaderKey(_, _, _) => {
@@ -213,20 +488,16 @@ impl RespParser {
             }
         };
 
-        let raw_protocol = &self.buffer[protocol.0..protocol.1];
+        let raw_version = &self.buffer[protocol.0..protocol.1];
         let raw_status_code = &self.buffer[status_code.0..status_code.1];
 
-        let protocol = unsafe { std::str::from_utf8_unchecked(raw_protocol) };
+        let version = Version::parse_bytes(raw_version).ok_or(ParseError::InvalidVersion)?;
         let status_code = match std::str::from_utf8(raw_status_code) {
             Ok(s) => s,
             Err(_) => {
                 return Err(ParseError::InvalidStatusCode);
             }
         };
-        if !status_code.is_ascii() {
-            return Err(ParseError::InvalidStatusCode);
-        }
-
         let parsed_status_code = match StatusCode::parse(status_code) {
             Some(s) => s,
             None => return Err(ParseError::InvalidStatusCode),
@@ -241,20 +512,30 @@ impl RespParser {
             let value_range = tmp_header.1;
             let raw_value = &self.buffer[value_range.0..value_range.1];
 
-            let key = unsafe { std::str::from_utf8_unchecked(raw_key) };
-            let value = unsafe { std::str::from_utf8_unchecked(raw_value) };
+            let key = std::str::from_utf8(raw_key).map_err(|_| ParseError::InvalidEncoding)?;
+            // Values with obs-text, which are not valid UTF-8, are kept as raw Bytes
+            let value: HeaderValue = match std::str::from_utf8(raw_value) {
+                Ok(value) => value.into(),
+                Err(_) => raw_value.into(),
+            };
 
-            // Use append to simply add the header at the end of the collection
-            // without checking for duplicates
-            headers.append(key, value);
+            // Duplicated Headers are handled based on the configured Policy
+            duplicates::append(
+                &mut headers,
+                HeaderKey::parsed(key),
+                value,
+                self.config.duplicates,
+            )?;
         }
 
-        Ok(Response::new(
-            protocol,
-            parsed_status_code,
-            headers,
-            std::mem::take(&mut self.body_buffer),
-        ))
+        let body = std::mem::take(&mut self.body_buffer);
+        let parser: &'a Self = self;
+
+        let response = Response::new(version, parsed_status_code, headers, body);
+        Ok(match parser.raw_head() {
+            Some(raw) => response.with_raw_head(raw),
+            None => response,
+        })
     }
 
     /// Finalizes the Response that is currently being Parsed by the Parser
@@ -262,13 +543,17 @@ impl RespParser {
     /// that the Response is independant of the Parser, unlike the normal `finish`
     /// Function. This freedom comes at the cost of more memory allocations and
     /// therefore less performance, so only use this where needed.
-    pub fn finish_owned<'a, 'owned>(&'a mut self) -> Result<Response<'owned>, ParseError> {
+    pub fn finish_owned<'owned>(&mut self) -> Result<Response<'owned>, ParseError> {
+        if let Some(e) = &self.error {
+            return Err(e.clone());
+        }
+
         let (protocol, status_code) = match &self.state {
-            ParseState::HeadersParsed(p, stc, _) => (p, stc),
-            ParseState::Nothing => {
+            ParseState::HeadersParsed(p, stc) => (p, stc),
+            ParseState::Nothing(_) => {
                 return Err(ParseError::MissingProtocol);
             }
-            ParseState::ProtocolParsed(_) => {
+            ParseState::ProtocolParsed(_, _) => {
                 return Err(ParseError::MissingStatusCode);
             }
             ParseState::HeaderKey(_, _, _) => {
@@ -279,20 +564,16 @@ impl RespParser {
             }
         };
 
-        let raw_protocol = &self.buffer[protocol.0..protocol.1];
+        let raw_version = &self.buffer[protocol.0..protocol.1];
         let raw_status_code = &self.buffer[status_code.0..status_code.1];
 
-        let protocol = unsafe { String::from_utf8_unchecked(raw_protocol.to_owned()) };
+        let version = Version::parse_bytes(raw_version).ok_or(ParseError::InvalidVersion)?;
         let status_code = match std::str::from_utf8(raw_status_code) {
             Ok(s) => s,
             Err(_) => {
                 return Err(ParseError::InvalidStatusCode);
             }
         };
-        if !status_code.is_ascii() {
-            return Err(ParseError::InvalidStatusCode);
-        }
-
         let parsed_status_code = match StatusCode::parse(status_code) {
             Some(s) => s,
             None => return Err(ParseError::InvalidStatusCode),
@@ -307,26 +588,38 @@ impl RespParser {
             let value_range = tmp_header.1;
             let raw_value = &self.buffer[value_range.0..value_range.1];
 
-            let key = unsafe { String::from_utf8_unchecked(raw_key.to_owned()) };
-            let value = unsafe { String::from_utf8_unchecked(raw_value.to_owned()) };
+            let key = std::str::from_utf8(raw_key).map_err(|_| ParseError::InvalidEncoding)?;
+            let value: HeaderValue = match String::from_utf8(raw_value.to_owned()) {
+                Ok(value) => value.into(),
+                Err(e) => e.into_bytes().into(),
+            };
 
-            // Use append to simply add the header at the end of the collection
-            // without checking for duplicates
-            headers.append(key, value);
+            // Duplicated Headers are handled based on the configured Policy
+            duplicates::append(
+                &mut headers,
+                HeaderKey::parsed_owned(key),
+                value,
+                self.config.duplicates,
+            )?;
         }
 
-        Ok(Response::new_owned(
-            protocol,
+        let response = Response::new(
+            version,
             parsed_status_code,
             headers,
             std::mem::take(&mut self.body_buffer),
-        ))
+        );
+        Ok(match self.raw_head() {
+            Some(raw) => response.with_raw_head(raw.to_owned()),
+            None => response,
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::header::HeaderValue;
 
     #[test]
     fn parser_parse_no_body() {
@@ -339,7 +632,7 @@ mod tests {
         headers.set("Test-1", "Value-1");
         assert_eq!(
             Ok(Response::new(
-                "HTTP/1.1",
+                Version::Http11,
                 StatusCode::OK,
                 headers,
                 "".as_bytes().to_vec()
@@ -358,7 +651,7 @@ mod tests {
         headers.set("Content-Length", "22");
         assert_eq!(
             Ok(Response::new(
-                "HTTP/1.1",
+                Version::Http11,
                 StatusCode::OK,
                 headers,
                 "This is just some body".as_bytes().to_vec()
@@ -378,7 +671,7 @@ mod tests {
         headers.set("Content-Length", "22");
         assert_eq!(
             Ok(Response::new(
-                "HTTP/1.1",
+                Version::Http11,
                 StatusCode::OK,
                 headers,
                 "This is just some body".as_bytes().to_vec()
@@ -398,7 +691,7 @@ mod tests {
         headers.set("Content-Length", "10");
         assert_eq!(
             Ok(Response::new(
-                "HTTP/1.1",
+                Version::Http11,
                 StatusCode::OK,
                 headers,
                 "This is ju".as_bytes().to_vec()
@@ -407,21 +700,275 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parser_parse_chunked_body() {
+        let block = "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n9\r\nDeveloper\r\n5\r\n Test\r\n0\r\n\r\n";
+        let mut parser = RespParser::new_capacity(1024);
+        assert_eq!((true, 0), parser.block_parse(block.as_bytes()));
+
+        let mut headers = Headers::new();
+        headers.set("Transfer-Encoding", "chunked");
+        assert_eq!(
+            Ok(Response::new(
+                Version::Http11,
+                StatusCode::OK,
+                headers,
+                "Developer Test".as_bytes().to_vec()
+            )),
+            parser.finish()
+        );
+    }
+    #[test]
+    fn parser_parse_chunked_body_multiple_blocks() {
+        let mut parser = RespParser::new_capacity(1024);
+        assert_eq!(
+            (false, 0),
+            parser.block_parse(
+                "HTTP/1.1 200 OK\r\nTransfer-Encoding: gzip, chunked\r\n\r\n9\r\nDevel".as_bytes()
+            )
+        );
+        assert_eq!(
+            (false, 0),
+            parser.block_parse("oper\r\n5\r\n Test\r\n".as_bytes())
+        );
+        assert_eq!((true, 0), parser.block_parse("0\r\n\r\n".as_bytes()));
+
+        let response = parser.finish_owned().unwrap();
+        assert_eq!("Developer Test".as_bytes(), response.body());
+    }
+
+    #[test]
+    fn parser_parse_chunked_body_with_trailers() {
+        let block = "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n9;ext=1\r\nDeveloper\r\n0\r\ngrpc-status: 0\r\n\r\n";
+        let mut parser = RespParser::new_capacity(1024);
+        assert_eq!((true, 0), parser.block_parse(block.as_bytes()));
+
+        assert_eq!(
+            Some(&HeaderValue::Str("0".to_owned())),
+            parser.trailers().unwrap().get("grpc-status")
+        );
+        assert_eq!("Developer".as_bytes(), parser.finish().unwrap().body());
+    }
+    #[test]
+    fn parser_parse_chunked_body_invalid() {
+        let block =
+            "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n3\r\nDeveloper\r\n0\r\n\r\n";
+        let mut parser = RespParser::new_capacity(1024);
+        assert_eq!((true, 12), parser.block_parse(block.as_bytes()));
+
+        assert_eq!(Err(ParseError::InvalidChunkTerminator), parser.finish());
+    }
+    #[test]
+    fn parser_parse_body_until_close() {
+        let block = "HTTP/1.1 200 OK\r\nTransfer-Encoding: gzip\r\nContent-Length: 4\r\n\r\nTest";
+        let mut parser = RespParser::new_capacity(1024);
+        assert_eq!((false, 0), parser.block_parse(block.as_bytes()));
+        assert!(parser.reads_until_close());
+        assert_eq!((false, 0), parser.block_parse(" Body".as_bytes()));
+
+        let response = parser.finish().unwrap();
+        assert_eq!("Test Body".as_bytes(), response.body());
+
+        let mut config = ParserConfig::default();
+        config.limits.max_body_size = 8;
+
+        let block = "HTTP/1.1 200 OK\r\nTransfer-Encoding: gzip\r\n\r\nTest";
+        let mut parser = RespParser::new_with_config(1024, config);
+        assert_eq!((false, 0), parser.block_parse(block.as_bytes()));
+        assert_eq!((true, 5), parser.block_parse(" Body".as_bytes()));
+        assert_eq!(Err(ParseError::BodyTooLarge), parser.finish());
+    }
+
+    #[test]
+    fn parser_limit_status_line() {
+        let mut config = ParserConfig::default();
+        config.limits.max_request_line_length = 8;
+
+        let block = "HTTP/1.1 200 OK\r\n\r\n";
+        let mut parser = RespParser::new_with_config(1024, config);
+        assert_eq!((true, 10), parser.block_parse(block.as_bytes()));
+        assert_eq!(Err(ParseError::RequestLineTooLong), parser.finish());
+    }
+    #[test]
+    fn parser_limit_header_count() {
+        let mut config = ParserConfig::default();
+        config.limits.max_header_count = 1;
+
+        let block = "HTTP/1.1 200 OK\r\nTest-1: Value-1\r\nTest-2: Value-2\r\n\r\n";
+        let mut parser = RespParser::new_with_config(1024, config);
+        assert_eq!((true, 2), parser.block_parse(block.as_bytes()));
+        assert_eq!(Err(ParseError::TooManyHeaders), parser.finish());
+    }
+    #[test]
+    fn parser_limit_body_size() {
+        let mut config = ParserConfig::default();
+        config.limits.max_body_size = 16;
+
+        let block = "HTTP/1.1 200 OK\r\nContent-Length: 22\r\n\r\nThis is just some body";
+        let mut parser = RespParser::new_with_config(1024, config);
+        assert_eq!((true, 22), parser.block_parse(block.as_bytes()));
+        assert_eq!(Err(ParseError::BodyTooLarge), parser.finish_owned());
+    }
+
+    #[test]
+    fn parser_invalid_content_length() {
+        let block = "HTTP/1.1 200 OK\r\nContent-Length: 1 0\r\n\r\n";
+        let mut parser = RespParser::new_capacity(1024);
+        assert_eq!((true, 0), parser.block_parse(block.as_bytes()));
+        assert_eq!(Err(ParseError::InvalidContentLength), parser.finish());
+    }
+    #[test]
+    fn parser_conflicting_content_length() {
+        let block = "HTTP/1.1 200 OK\r\nContent-Length: 4, 5\r\n\r\nbody";
+        let mut parser = RespParser::new_capacity(1024);
+        assert_eq!((true, 4), parser.block_parse(block.as_bytes()));
+        assert_eq!(Err(ParseError::ConflictingContentLength), parser.finish());
+    }
+
+    #[test]
+    fn parser_invalid_encoding_header() {
+        let block = b"HTTP/1.1 200 OK\r\nTest-1: Val\xc3\x28ue-1\r\n\r\n";
+        let mut parser = RespParser::new_capacity(1024);
+        assert_eq!((true, 0), parser.block_parse(block));
+        let response = parser.finish().unwrap();
+        assert_eq!(
+            Some(&HeaderValue::BytesRef(b"Val\xc3\x28ue-1")),
+            response.headers().get("Test-1")
+        );
+        assert_eq!(block.to_vec(), response.serialize().0);
+
+        let mut parser = RespParser::new_capacity(1024);
+        assert_eq!((true, 0), parser.block_parse(block));
+        let response = parser.finish_owned().unwrap();
+        assert_eq!(
+            Some(&HeaderValue::Bytes(b"Val\xc3\x28ue-1".to_vec())),
+            response.headers().get("Test-1")
+        );
+        assert_eq!(block.to_vec(), response.to_owned().serialize().0);
+    }
+
+    #[test]
+    fn parser_header_validation() {
+        let block = "HTTP/1.1 200 OK\r\nTest-1:Value-1\r\nTest-2: \t Value-2 \t\r\n\r\n";
+        let mut parser = RespParser::new_capacity(1024);
+        assert_eq!((true, 0), parser.block_parse(block.as_bytes()));
+
+        let response = parser.finish().unwrap();
+        assert_eq!(Version::Http11, response.version());
+        assert_eq!(&StatusCode::OK, response.status_code());
+        assert_eq!(2, response.headers().get_header_count());
+        assert_eq!(
+            Some(&HeaderValue::StrRef("Value-1")),
+            response.headers().get("Test-1")
+        );
+        assert_eq!(
+            Some(&HeaderValue::StrRef("Value-2")),
+            response.headers().get("Test-2")
+        );
+        assert_eq!("".as_bytes(), response.body());
+
+        let block = b"HTTP/1.1 200 OK\r\nTest-\xff: Value-1\r\n\r\n";
+        let mut parser = RespParser::new_capacity(1024);
+        assert_eq!((true, 12), parser.block_parse(block));
+        assert_eq!(Err(ParseError::InvalidHeaderName), parser.finish());
+
+        let block = b"HTTP/1.1 200 OK\r\nTest-1: Val\x01ue\r\n\r\n";
+        let mut parser = RespParser::new_capacity(1024);
+        assert_eq!((true, 2), parser.block_parse(block));
+        assert_eq!(Err(ParseError::InvalidHeaderValue), parser.finish());
+    }
+    #[test]
+    fn parser_version() {
+        let block = "HTTP/1.0 200 OK\r\n\r\n";
+        let mut parser = RespParser::new_capacity(1024);
+        assert_eq!((true, 0), parser.block_parse(block.as_bytes()));
+        assert_eq!(Version::Http10, parser.finish().unwrap().version());
+
+        let block = "HXXP/9 200 OK\r\n\r\n";
+        let mut parser = RespParser::new_capacity(1024);
+        assert_eq!((true, 10), parser.block_parse(block.as_bytes()));
+        assert_eq!(Err(ParseError::InvalidVersion), parser.finish());
+    }
+    #[test]
+    fn parser_status_code() {
+        let block = "HTTP/1.1 429 Slow Down\r\n\r\n";
+        let mut parser = RespParser::new_capacity(1024);
+        assert_eq!((true, 0), parser.block_parse(block.as_bytes()));
+        let response = parser.finish().unwrap();
+        assert_eq!(&StatusCode::TooManyRequests, response.status_code());
+        assert_eq!(
+            "HTTP/1.1 429 Slow Down\r\n\r\n".as_bytes(),
+            &response.serialize().0[..]
+        );
+
+        let block = "HTTP/1.1 2000 OK\r\n\r\n";
+        let mut parser = RespParser::new_capacity(1024);
+        assert_eq!((true, 0), parser.block_parse(block.as_bytes()));
+        assert_eq!(Err(ParseError::InvalidStatusCode), parser.finish());
+    }
+    #[test]
+    fn parser_strict_profile() {
+        let cases: &[(&str, ParseError)] = &[
+            (
+                "HTTP/1.1 200 OK\nTest-1: Value-1\n\n",
+                ParseError::InvalidLineEnding,
+            ),
+            ("\r\nHTTP/1.1 200 OK\r\n\r\n", ParseError::LeadingEmptyLine),
+            ("HTTP/1.1  200 OK\r\n\r\n", ParseError::InvalidWhitespace),
+            (
+                "HTTP/1.1 200 OK\r\nTest-1: Value-1\r\n continued\r\n\r\n",
+                ParseError::ObsoleteLineFolding,
+            ),
+        ];
+
+        for (block, error) in cases {
+            let mut parser = RespParser::new_capacity(1024);
+            let (done, _) = parser.block_parse(block.as_bytes());
+            assert!(done, "{:?}", block);
+            assert_eq!(Err(error.clone()), parser.finish(), "{:?}", block);
+        }
+    }
+    #[test]
+    fn parser_lenient_profile() {
+        let config = ParserConfig {
+            profile: ParseProfile::Lenient,
+            ..ParserConfig::default()
+        };
+        let block = "\nHTTP/1.1\t 200 OK \nTest-1: Value-1\n continued\nContent-Length: 4\n\nTest";
+
+        let mut parser = RespParser::new_with_config(1024, config);
+        assert_eq!((true, 0), parser.block_parse(block.as_bytes()));
+
+        let response = parser.finish().unwrap();
+        assert_eq!(Version::Http11, response.version());
+        assert_eq!(&StatusCode::OK, response.status_code());
+        assert_eq!(2, response.headers().get_header_count());
+        assert_eq!(
+            Some(&HeaderValue::StrRef("Value-1  continued")),
+            response.headers().get("Test-1")
+        );
+        assert_eq!(
+            Some(&HeaderValue::StrRef("4")),
+            response.headers().get("Content-Length")
+        );
+        assert_eq!("Test".as_bytes(), response.body());
+    }
     #[test]
     fn parser_fuzzing_bug_0() {
         let block = vec![63, 32, 243, 13, 33, 13, 33, 242];
         let mut parser = RespParser::new_capacity(1024);
 
-        assert_eq!((true, 1), parser.block_parse(&block));
+        assert_eq!((true, 6), parser.block_parse(&block));
         // Expect this operation to not return a valid value
-        assert_eq!(true, parser.finish().is_err());
+        assert!(parser.finish().is_err());
     }
     #[test]
     fn parser_fuzzing_bug_1() {
         let block = vec![32, 13, 58, 13, 32, 13, 93];
         let mut parser = RespParser::new_capacity(1024);
 
-        assert_eq!((true, 2), parser.block_parse(&block));
+        assert_eq!((true, 6), parser.block_parse(&block));
+        assert!(parser.finish().is_err());
     }
     #[test]
     fn parser_fuzzing_bug_2() {
@@ -430,8 +977,8 @@ mod tests {
         ];
         let mut parser = RespParser::new_capacity(1024);
 
-        assert_eq!((true, 3), parser.block_parse(&block));
-        assert_eq!(true, parser.finish().is_err());
+        assert_eq!((true, 16), parser.block_parse(&block));
+        assert!(parser.finish().is_err());
     }
     #[test]
     fn parser_fuzzing_bug_3() {
@@ -441,7 +988,73 @@ mod tests {
         ];
         let mut parser = RespParser::new_capacity(1024);
 
-        assert_eq!((true, 3), parser.block_parse(&block));
-        assert_eq!(true, parser.finish().is_err());
+        assert_eq!((true, 35), parser.block_parse(&block));
+        assert!(parser.finish().is_err());
+    }
+
+    #[test]
+    fn parser_preserve_raw() {
+        let config = ParserConfig {
+            preserve_raw: true,
+            ..ParserConfig::default()
+        };
+        let head = "HTTP/1.1 200 Fine\r\ncontent-length:  4\r\nX-Custom: Value\r\n\r\n";
+
+        let mut parser = RespParser::new_with_config(1024, config);
+        assert_eq!(
+            (true, 0),
+            parser.block_parse(format!("{}Test", head).as_bytes())
+        );
+        let response = parser.finish().unwrap();
+
+        let raw = response.raw_head().unwrap();
+        assert_eq!(b"200", raw.get(raw.start_line_parts()[1]));
+        assert_eq!(b"Fine", raw.get(raw.start_line_parts()[2]));
+        assert_eq!(
+            (head.as_bytes(), "Test".as_bytes()),
+            (&response.serialize().0[..], response.serialize().1)
+        );
+
+        let mut owned = response.to_owned();
+        owned.add_header("X-Custom", "Changed");
+        assert_eq!(
+            "HTTP/1.1 200 Fine\r\ncontent-length:  4\r\nX-Custom: Changed\r\n\r\n".as_bytes(),
+            &owned.serialize().0
+        );
+    }
+    #[test]
+    fn parser_preserve_raw_multiple_folds() {
+        let config = ParserConfig {
+            profile: ParseProfile::Lenient,
+            preserve_raw: true,
+            ..ParserConfig::default()
+        };
+        let head = "HTTP/1.1 204 No Content\r\nX-A: one\r\n\t\r\n two\r\n\tthree\r\n\r\n";
+
+        let mut parser = RespParser::new_with_config(1024, config);
+        assert_eq!((true, 0), parser.block_parse(head.as_bytes()));
+        let response = parser.finish().unwrap();
+
+        let raw = response.raw_head().unwrap();
+        assert_eq!(head.as_bytes(), raw.bytes());
+        assert_eq!(
+            Some(&HeaderValue::StrRef("one      two   three")),
+            response.headers().get("X-A")
+        );
+    }
+    #[test]
+    fn parser_preserve_raw_owned() {
+        let config = ParserConfig {
+            preserve_raw: true,
+            ..ParserConfig::default()
+        };
+        let head = "HTTP/1.1 404\r\nServer: test\r\n\r\n";
+
+        let mut parser = RespParser::new_with_config(1024, config);
+        assert_eq!((true, 0), parser.block_parse(head.as_bytes()));
+        let response = parser.finish_owned().unwrap();
+        parser.clear();
+
+        assert_eq!(head.as_bytes(), &response.serialize().0);
     }
 }
@@ -0,0 +1,15 @@
+mod target;
+pub use target::{RequestTarget, TargetForm};
+
+mod percent;
+pub use percent::{percent_decode, percent_encode};
+
+mod query;
+pub use query::Query;
+
+mod path;
+pub use path::{normalize_path, PathError};
+
+mod host;
+pub(crate) use host::default_port;
+pub use host::{Host, HostName};
@@ -0,0 +1,191 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use crate::streaming_parser::{ParseError, ParseResult};
+
+/// The Name part of a Host
+#[derive(Debug, Clone, PartialEq)]
+pub enum HostName<'a> {
+    /// A registered Name, like `example.com`
+    Name(&'a str),
+    /// An IPv4-Address, like `127.0.0.1`
+    Ipv4(Ipv4Addr),
+    /// An IPv6-Address, which is written in Brackets, like `[::1]`
+    Ipv6(Ipv6Addr),
+}
+
+/// A parsed Host, as it is used in the `Host` Header and the
+/// Authority of a Request-Target
+#[derive(Debug, Clone)]
+pub struct Host<'a> {
+    name: HostName<'a>,
+    port: Option<u16>,
+}
+
+impl<'a> Host<'a> {
+    /// Parses the raw Host, like `example.com:8080`
+    pub fn parse(raw: &'a str) -> ParseResult<Self> {
+        let (name, raw_port) = if let Some(rest) = raw.strip_prefix('[') {
+            let (address, rest) = rest.split_once(']').ok_or(ParseError::InvalidHost)?;
+            let address = address.parse().map_err(|_| ParseError::InvalidHost)?;
+            let raw_port = match rest {
+                "" => None,
+                _ => Some(rest.strip_prefix(':').ok_or(ParseError::InvalidHost)?),
+            };
+            (HostName::Ipv6(address), raw_port)
+        } else {
+            let (name, raw_port) = match raw.split_once(':') {
+                Some((name, port)) => (name, Some(port)),
+                None => (raw, None),
+            };
+            if name.is_empty() || !name.bytes().all(is_reg_name_char) {
+                return Err(ParseError::InvalidHost);
+            }
+
+            match name.parse() {
+                Ok(address) => (HostName::Ipv4(address), raw_port),
+                Err(_) => (HostName::Name(name), raw_port),
+            }
+        };
+
+        // The Port may be empty, which is the same as no Port
+        let port = match raw_port {
+            Some(raw_port) if !raw_port.is_empty() => {
+                if !raw_port.bytes().all(|b| b.is_ascii_digit()) {
+                    return Err(ParseError::InvalidHost);
+                }
+                Some(raw_port.parse().map_err(|_| ParseError::InvalidHost)?)
+            }
+            _ => None,
+        };
+
+        Ok(Self { name, port })
+    }
+
+    /// Returns the Name of the Host
+    pub fn name(&self) -> &HostName<'a> {
+        &self.name
+    }
+    /// Returns the Port, if one was given
+    pub fn port(&self) -> Option<u16> {
+        self.port
+    }
+
+    /// Checks if both Hosts refer to the same Host, where Names
+    /// are compared case-insensitive and a missing Port is the
+    /// same as the given default Port
+    pub fn matches(&self, other: &Host<'_>, default_port: Option<u16>) -> bool {
+        let same_name = match (&self.name, &other.name) {
+            (HostName::Name(own), HostName::Name(other)) => own.eq_ignore_ascii_case(other),
+            (HostName::Ipv4(own), HostName::Ipv4(other)) => own == other,
+            (HostName::Ipv6(own), HostName::Ipv6(other)) => own == other,
+            _ => false,
+        };
+
+        same_name && self.port.or(default_port) == other.port.or(default_port)
+    }
+}
+
+impl std::fmt::Display for Host<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.name {
+            HostName::Name(name) => write!(f, "{}", name)?,
+            HostName::Ipv4(address) => write!(f, "{}", address)?,
+            HostName::Ipv6(address) => write!(f, "[{}]", address)?,
+        };
+        match self.port {
+            Some(port) => write!(f, ":{}", port),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Checks if the Byte can be used in a `reg-name`, as defined in
+/// [RFC 3986 3.2.2](https://www.rfc-editor.org/rfc/rfc3986#section-3.2.2)
+fn is_reg_name_char(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric()
+        || matches!(
+            byte,
+            b'-' | b'.'
+                | b'_'
+                | b'~'
+                | b'%'
+                | b'!'
+                | b'$'
+                | b'&'
+                | b'\''
+                | b'('
+                | b')'
+                | b'*'
+                | b'+'
+                | b','
+                | b';'
+                | b'='
+        )
+}
+
+/// Returns the default Port for the given Scheme
+pub(crate) fn default_port(scheme: &str) -> Option<u16> {
+    if scheme.eq_ignore_ascii_case("http") {
+        Some(80)
+    } else if scheme.eq_ignore_ascii_case("https") {
+        Some(443)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_name() {
+        let host = Host::parse("Example.com:8080").unwrap();
+        assert_eq!(&HostName::Name("Example.com"), host.name());
+        assert_eq!(Some(8080), host.port());
+
+        let host = Host::parse("example.com:").unwrap();
+        assert_eq!(None, host.port());
+    }
+    #[test]
+    fn parse_ip() {
+        let host = Host::parse("127.0.0.1").unwrap();
+        assert_eq!(&HostName::Ipv4(Ipv4Addr::LOCALHOST), host.name());
+        assert_eq!(None, host.port());
+
+        let host = Host::parse("[::1]:443").unwrap();
+        assert_eq!(&HostName::Ipv6(Ipv6Addr::LOCALHOST), host.name());
+        assert_eq!(Some(443), host.port());
+        assert_eq!("[::1]:443", host.to_string());
+    }
+    #[test]
+    fn parse_invalid() {
+        for raw in [
+            "",
+            ":80",
+            "example.com:http",
+            "example.com:99999",
+            "user@example.com",
+            "exa mple.com",
+            "[::1",
+            "[::1]x",
+            "[not-an-ip]",
+            "a:1:2",
+        ] {
+            assert_eq!(
+                Err(ParseError::InvalidHost),
+                Host::parse(raw).map(|_| ()),
+                "{}",
+                raw
+            );
+        }
+    }
+
+    #[test]
+    fn matches() {
+        let host = Host::parse("EXAMPLE.com").unwrap();
+        assert!(host.matches(&Host::parse("example.com:80").unwrap(), Some(80)));
+        assert!(!host.matches(&Host::parse("example.com:8080").unwrap(), Some(80)));
+        assert!(!host.matches(&Host::parse("example.org").unwrap(), Some(80)));
+    }
+}
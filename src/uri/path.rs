@@ -0,0 +1,150 @@
+use crate::uri::percent::{decode_triplet, is_unreserved};
+
+/// The Errors that can occur while normalising a Path
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathError {
+    /// The Request-Target could not be parsed
+    InvalidTarget,
+    /// The Path does not start with a `/`
+    NotAbsolute,
+    /// A percent-encoded Triplet is malformed
+    InvalidEncoding,
+    /// The Path contains an encoded Separator (`%2F` or `%5C`)
+    EncodedSeparator,
+    /// The Path contains an encoded Dot-Segment, like `%2e%2e`
+    EncodedDotSegment,
+}
+
+impl std::fmt::Display for PathError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            Self::InvalidTarget => write!(f, "Invalid Request-Target"),
+            Self::NotAbsolute => write!(f, "Path is not absolute"),
+            Self::InvalidEncoding => write!(f, "Invalid Percent-Encoding"),
+            Self::EncodedSeparator => write!(f, "Encoded Separator"),
+            Self::EncodedDotSegment => write!(f, "Encoded Dot-Segment"),
+        }
+    }
+}
+
+/// Normalises the given absolute Path, without a Query, by
+/// * decoding percent-encoded `unreserved` Characters and using
+///   uppercase Hex-Digits for all other Triplets
+/// * collapsing duplicate Slashes
+/// * removing Dot-Segments, as described in
+///   [RFC 3986 5.2.4](https://www.rfc-editor.org/rfc/rfc3986#section-5.2.4),
+///   where `..` can never go above the Root
+///
+/// Encoded Separators and encoded Dot-Segments are rejected, as they
+/// could be used to escape the Root after being decoded by a later Step
+pub fn normalize_path(path: &str) -> Result<String, PathError> {
+    if path.is_empty() {
+        return Ok("/".to_owned());
+    }
+    let path = path.strip_prefix('/').ok_or(PathError::NotAbsolute)?;
+
+    let mut segments: Vec<String> = Vec::new();
+    let mut trailing_slash = false;
+    for raw_segment in path.split('/') {
+        let segment = normalize_segment(raw_segment)?;
+        trailing_slash = true;
+        match segment.as_str() {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            _ => {
+                segments.push(segment);
+                trailing_slash = false;
+            }
+        };
+    }
+
+    let mut result = String::with_capacity(path.len() + 1);
+    for segment in segments.iter() {
+        result.push('/');
+        result.push_str(segment);
+    }
+    if trailing_slash || result.is_empty() {
+        result.push('/');
+    }
+    Ok(result)
+}
+
+fn normalize_segment(raw: &str) -> Result<String, PathError> {
+    let bytes = raw.as_bytes();
+    let mut result = String::with_capacity(raw.len());
+    let mut encoded = false;
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] != b'%' {
+            // The Segment is already valid UTF-8, so this can only split
+            // at Character-Boundaries
+            let end = raw[index..].find('%').map_or(raw.len(), |e| index + e);
+            result.push_str(&raw[index..end]);
+            index = end;
+            continue;
+        }
+
+        let byte = decode_triplet(&bytes[index..]).ok_or(PathError::InvalidEncoding)?;
+        match byte {
+            b'/' | b'\\' => return Err(PathError::EncodedSeparator),
+            _ if is_unreserved(byte) => result.push(byte as char),
+            _ => result.push_str(&format!("%{:02X}", byte)),
+        };
+        encoded = true;
+        index += 3;
+    }
+
+    if encoded && (result == "." || result == "..") {
+        return Err(PathError::EncodedDotSegment);
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_dot_segments() {
+        assert_eq!(Ok("/a/c".to_owned()), normalize_path("/a/b/../c"));
+        assert_eq!(Ok("/a/".to_owned()), normalize_path("/a/b/.."));
+        assert_eq!(Ok("/a/b/".to_owned()), normalize_path("/a/./b/."));
+        assert_eq!(
+            Ok("/etc/passwd".to_owned()),
+            normalize_path("/../../etc/passwd")
+        );
+        assert_eq!(Ok("/".to_owned()), normalize_path("/.."));
+        assert_eq!(Ok("/..a/b..".to_owned()), normalize_path("/..a/b.."));
+    }
+    #[test]
+    fn normalize_slashes() {
+        assert_eq!(Ok("/a/b/".to_owned()), normalize_path("//a///b//"));
+        assert_eq!(Ok("/".to_owned()), normalize_path("/"));
+        assert_eq!(Ok("/".to_owned()), normalize_path(""));
+    }
+    #[test]
+    fn normalize_encoding() {
+        assert_eq!(
+            Ok("/abc-~/%20%C3%A4".to_owned()),
+            normalize_path("/%61b%63%2d%7e/%20%c3%a4")
+        );
+        assert_eq!(Ok("/ä".to_owned()), normalize_path("/ä"));
+    }
+
+    #[test]
+    fn normalize_rejected() {
+        assert_eq!(Err(PathError::NotAbsolute), normalize_path("a/b"));
+        assert_eq!(Err(PathError::InvalidEncoding), normalize_path("/a%2"));
+        assert_eq!(Err(PathError::InvalidEncoding), normalize_path("/a%zz"));
+        assert_eq!(Err(PathError::EncodedSeparator), normalize_path("/a%2fb"));
+        assert_eq!(Err(PathError::EncodedSeparator), normalize_path("/a%5Cb"));
+        assert_eq!(
+            Err(PathError::EncodedDotSegment),
+            normalize_path("/a/%2e%2e/b")
+        );
+        assert_eq!(Err(PathError::EncodedDotSegment), normalize_path("/a/.%2E"));
+        assert_eq!(Err(PathError::EncodedDotSegment), normalize_path("/%2e"));
+    }
+}
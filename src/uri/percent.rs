@@ -0,0 +1,137 @@
+use std::borrow::Cow;
+
+use crate::streaming_parser::{ParseError, ParseResult};
+
+/// Checks if the given Byte is an `unreserved` Character, as defined
+/// in [RFC 3986 2.3](https://www.rfc-editor.org/rfc/rfc3986#section-2.3)
+pub(crate) fn is_unreserved(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~')
+}
+
+fn hex_value(byte: u8) -> Option<u8> {
+    (byte as char).to_digit(16).map(|d| d as u8)
+}
+
+/// Decodes the percent-encoded Triplet at the start of the Data,
+/// returns None if it is not a valid Triplet
+pub(crate) fn decode_triplet(data: &[u8]) -> Option<u8> {
+    match data {
+        [b'%', high, low, ..] => Some(hex_value(*high)? << 4 | hex_value(*low)?),
+        _ => None,
+    }
+}
+
+/// Percent-Encodes every Character of the Input that is not
+/// an `unreserved` Character
+pub fn percent_encode(input: &str) -> Cow<'_, str> {
+    if input.bytes().all(is_unreserved) {
+        return Cow::Borrowed(input);
+    }
+
+    let mut result = String::with_capacity(input.len() * 3);
+    for byte in input.bytes() {
+        if is_unreserved(byte) {
+            result.push(byte as char);
+        } else {
+            result.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    Cow::Owned(result)
+}
+
+/// Decodes all the percent-encoded Triplets in the Input
+///
+/// Returns an Error if a Triplet is malformed or the decoded
+/// Data is not valid UTF-8
+pub fn percent_decode(input: &str) -> ParseResult<Cow<'_, str>> {
+    if !input.contains('%') {
+        return Ok(Cow::Borrowed(input));
+    }
+
+    let raw = input.as_bytes();
+    let mut result = Vec::with_capacity(raw.len());
+    let mut index = 0;
+    while index < raw.len() {
+        if raw[index] == b'%' {
+            let byte = decode_triplet(&raw[index..]).ok_or(ParseError::InvalidPercentEncoding)?;
+            result.push(byte);
+            index += 3;
+        } else {
+            result.push(raw[index]);
+            index += 1;
+        }
+    }
+
+    String::from_utf8(result)
+        .map(Cow::Owned)
+        .map_err(|_| ParseError::InvalidPercentEncoding)
+}
+
+/// Decodes a single Component of a Query, where a `+` stands for
+/// a Space and malformed Triplets are kept as is, while decoded Bytes
+/// that are not valid UTF-8 are replaced with `U+FFFD`
+pub(crate) fn decode_query_component(input: &str) -> Cow<'_, str> {
+    if !input.contains(['%', '+']) {
+        return Cow::Borrowed(input);
+    }
+
+    let raw = input.as_bytes();
+    let mut result = Vec::with_capacity(raw.len());
+    let mut index = 0;
+    while index < raw.len() {
+        match (raw[index], decode_triplet(&raw[index..])) {
+            (b'%', Some(byte)) => {
+                result.push(byte);
+                index += 3;
+                continue;
+            }
+            (b'+', _) => result.push(b' '),
+            (byte, _) => result.push(byte),
+        };
+        index += 1;
+    }
+
+    Cow::Owned(String::from_utf8_lossy(&result).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode() {
+        assert_eq!("simple-value_1.~", percent_encode("simple-value_1.~"));
+        assert_eq!("a%20b%2Fc%3F%C3%A4", percent_encode("a b/c?ä"));
+    }
+
+    #[test]
+    fn decode() {
+        assert_eq!(Ok(Cow::Borrowed("plain")), percent_decode("plain"));
+        assert_eq!(Ok("a b/c?ä".into()), percent_decode("a%20b%2fc%3F%C3%A4"));
+        assert_eq!(Ok("a+b".into()), percent_decode("a+b"));
+    }
+    #[test]
+    fn decode_invalid() {
+        assert_eq!(Err(ParseError::InvalidPercentEncoding), percent_decode("%"));
+        assert_eq!(
+            Err(ParseError::InvalidPercentEncoding),
+            percent_decode("%2")
+        );
+        assert_eq!(
+            Err(ParseError::InvalidPercentEncoding),
+            percent_decode("%zz")
+        );
+        assert_eq!(
+            Err(ParseError::InvalidPercentEncoding),
+            percent_decode("%ff")
+        );
+    }
+
+    #[test]
+    fn decode_query() {
+        assert_eq!("a b c", decode_query_component("a+b%20c"));
+        assert_eq!("100%", decode_query_component("100%"));
+        assert_eq!("%zz", decode_query_component("%zz"));
+        assert_eq!("a\u{FFFD}b", decode_query_component("a%ffb"));
+    }
+}
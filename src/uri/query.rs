@@ -0,0 +1,152 @@
+use std::borrow::Cow;
+
+use crate::uri::percent::{decode_query_component, percent_encode};
+
+/// The decoded Parameters of a Query-String, like `a=1&b=2`
+///
+/// A Key can appear multiple times and Parameters keep the
+/// Order in which they were added
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Query<'a> {
+    params: Vec<(Cow<'a, str>, Cow<'a, str>)>,
+}
+
+impl<'a> Query<'a> {
+    /// Creates a new empty Query
+    pub fn new() -> Self {
+        Self { params: Vec::new() }
+    }
+
+    /// Parses the raw Query-String, without the leading `?`
+    ///
+    /// Keys and Values are percent-decoded and a `+` is decoded
+    /// as a Space, a Parameter without a `=` has an empty Value
+    pub fn parse(raw: &'a str) -> Self {
+        let params = raw
+            .split('&')
+            .filter(|param| !param.is_empty())
+            .map(|param| {
+                let (key, value) = param.split_once('=').unwrap_or((param, ""));
+                (decode_query_component(key), decode_query_component(value))
+            })
+            .collect();
+
+        Self { params }
+    }
+
+    /// Returns the Value of the first Parameter with the given Key
+    pub fn get<'s>(&'s self, key: &'s str) -> Option<&'s str> {
+        self.get_all(key).next()
+    }
+    /// Returns the Values of all the Parameters with the given Key
+    pub fn get_all<'s>(&'s self, key: &'s str) -> impl Iterator<Item = &'s str> {
+        self.params
+            .iter()
+            .filter(move |(k, _)| k == key)
+            .map(|(_, v)| v.as_ref())
+    }
+    /// Returns an Iterator over all the Key-Value Pairs
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.params.iter().map(|(k, v)| (k.as_ref(), v.as_ref()))
+    }
+
+    /// Sets the Value of the Parameter with the given Key, which
+    /// removes all other Parameters with the same Key
+    pub fn set<K, V>(&mut self, key: K, value: V)
+    where
+        K: Into<Cow<'a, str>>,
+        V: Into<Cow<'a, str>>,
+    {
+        let key = key.into();
+        self.remove(&key);
+        self.params.push((key, value.into()));
+    }
+    /// Appends the Parameter at the End, without removing any
+    /// other Parameters with the same Key
+    pub fn append<K, V>(&mut self, key: K, value: V)
+    where
+        K: Into<Cow<'a, str>>,
+        V: Into<Cow<'a, str>>,
+    {
+        self.params.push((key.into(), value.into()));
+    }
+    /// Removes all the Parameters with the given Key
+    pub fn remove(&mut self, key: &str) {
+        self.params.retain(|(k, _)| k != key);
+    }
+
+    /// Returns the Number of Parameters
+    pub fn len(&self) -> usize {
+        self.params.len()
+    }
+    /// Checks if there are no Parameters
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
+    }
+
+    /// Converts all the Parameters into owned Data to produce an
+    /// independant Query
+    pub fn into_owned(self) -> Query<'static> {
+        let params = self
+            .params
+            .into_iter()
+            .map(|(k, v)| (Cow::Owned(k.into_owned()), Cow::Owned(v.into_owned())))
+            .collect();
+
+        Query { params }
+    }
+
+    /// Serializes the Parameters into a percent-encoded Query-String,
+    /// without the leading `?`
+    pub fn serialize(&self) -> String {
+        let mut result = String::new();
+        for (index, (key, value)) in self.params.iter().enumerate() {
+            if index > 0 {
+                result.push('&');
+            }
+            result.push_str(&percent_encode(key));
+            result.push('=');
+            result.push_str(&percent_encode(value));
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let query = Query::parse("a=1&b=two+words&a=%32&flag&&c=");
+        assert_eq!(5, query.len());
+        assert_eq!(Some("1"), query.get("a"));
+        assert_eq!(vec!["1", "2"], query.get_all("a").collect::<Vec<_>>());
+        assert_eq!(Some("two words"), query.get("b"));
+        assert_eq!(Some(""), query.get("flag"));
+        assert_eq!(Some(""), query.get("c"));
+        assert_eq!(None, query.get("d"));
+    }
+
+    #[test]
+    fn modify() {
+        let mut query = Query::parse("a=1&b=2&a=3");
+        query.set("a", "new value");
+        query.append("c", "1");
+        query.append("c", "2");
+        query.remove("b");
+        assert_eq!(
+            vec![("a", "new value"), ("c", "1"), ("c", "2")],
+            query.iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn serialize() {
+        let mut query = Query::new();
+        query.append("key", "some value&more");
+        query.append("ä", "");
+        assert_eq!("key=some%20value%26more&%C3%A4=", query.serialize());
+        assert_eq!(query, Query::parse(&query.serialize()));
+    }
+}
@@ -0,0 +1,280 @@
+use crate::{
+    general::StringContainer, streaming_parser::ParseError, streaming_parser::ParseResult, Method,
+};
+
+/// The different Forms a Request-Target can have, as defined in
+/// [RFC 9112 3.2](https://www.rfc-editor.org/rfc/rfc9112#section-3.2)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetForm {
+    /// An absolute Path with an optional Query, like `/path?query`
+    Origin,
+    /// An absolute URI, like `http://example.com/path`, mostly used
+    /// for Requests to Proxies
+    Absolute,
+    /// Only the Authority, like `example.com:443`, which is only
+    /// used for `CONNECT` Requests
+    Authority,
+    /// The single `*`, which is only used for `OPTIONS` Requests
+    Asterisk,
+}
+
+/// The parsed Request-Target of a Request
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestTarget<'a> {
+    raw: StringContainer<'a>,
+    form: TargetForm,
+    scheme: Option<(usize, usize)>,
+    authority: Option<(usize, usize)>,
+    path: (usize, usize),
+    query: Option<(usize, usize)>,
+}
+
+impl<'a> RequestTarget<'a> {
+    /// Parses the raw Request-Target and checks that its Form is
+    /// allowed for the given Method
+    pub fn parse(raw: &'a str, method: &Method) -> ParseResult<Self> {
+        Self::parse_container(StringContainer::Ref(raw), method).map_err(|(_, e)| e)
+    }
+
+    /// Parses the Request-Target like [`parse`](Self::parse), but also
+    /// accepts an owned Target, which is handed back together with the
+    /// Error if it is invalid
+    pub(crate) fn parse_container(
+        raw: StringContainer<'a>,
+        method: &Method,
+    ) -> Result<Self, (StringContainer<'a>, ParseError)> {
+        match split_target(raw.as_ref(), method) {
+            Ok((form, scheme, authority, path, query)) => Ok(Self {
+                raw,
+                form,
+                scheme,
+                authority,
+                path,
+                query,
+            }),
+            Err(e) => Err((raw, e)),
+        }
+    }
+
+    fn get(&self, (start, end): (usize, usize)) -> &str {
+        &self.raw.as_ref()[start..end]
+    }
+
+    /// Returns the entire raw Target
+    pub fn as_str(&self) -> &str {
+        self.raw.as_ref()
+    }
+    /// Returns the Form of the Target
+    pub fn form(&self) -> TargetForm {
+        self.form
+    }
+    /// Returns the Scheme, only present in the absolute-form
+    pub fn scheme(&self) -> Option<&str> {
+        self.scheme.map(|range| self.get(range))
+    }
+    /// Returns the Authority, only present in the absolute-form
+    /// and authority-form
+    pub fn authority(&self) -> Option<&str> {
+        self.authority.map(|range| self.get(range))
+    }
+    /// Returns the Path without the Query, which is empty for the
+    /// authority-form and `*` for the asterisk-form
+    pub fn path(&self) -> &str {
+        self.get(self.path)
+    }
+    /// Returns the Query without the leading `?`, if present
+    pub fn query(&self) -> Option<&str> {
+        self.query.map(|range| self.get(range))
+    }
+}
+
+/// The Form of a Request-Target and the Ranges of its Scheme,
+/// Authority, Path and Query
+type TargetParts = (
+    TargetForm,
+    Option<(usize, usize)>,
+    Option<(usize, usize)>,
+    (usize, usize),
+    Option<(usize, usize)>,
+);
+
+/// Splits the raw Request-Target into its Parts and checks that its
+/// Form is allowed for the given Method
+fn split_target(value: &str, method: &Method) -> ParseResult<TargetParts> {
+    if value.is_empty()
+        || value
+            .bytes()
+            .any(|b| !(0x21..0x7F).contains(&b) || b == b'#')
+    {
+        return Err(ParseError::InvalidTarget);
+    }
+
+    let end = value.len();
+    let (form, scheme, authority, path, query) = if value == "*" {
+        (TargetForm::Asterisk, None, None, (0, end), None)
+    } else if value.starts_with('/') {
+        let (path, query) = split_query(value, 0);
+        (TargetForm::Origin, None, None, path, query)
+    } else if let Some(scheme_end) = split_scheme(value) {
+        let authority_start = scheme_end + 3;
+        let authority_end = value[authority_start..]
+            .find(['/', '?'])
+            .map(|i| authority_start + i)
+            .unwrap_or(end);
+        if authority_start == authority_end {
+            return Err(ParseError::InvalidTarget);
+        }
+
+        let (path, query) = split_query(value, authority_end);
+        (
+            TargetForm::Absolute,
+            Some((0, scheme_end)),
+            Some((authority_start, authority_end)),
+            path,
+            query,
+        )
+    } else {
+        if !is_authority_form(value) {
+            return Err(ParseError::InvalidTarget);
+        }
+        (
+            TargetForm::Authority,
+            None,
+            Some((0, end)),
+            (end, end),
+            None,
+        )
+    };
+
+    let allowed = match form {
+        TargetForm::Origin | TargetForm::Absolute => *method != Method::CONNECT,
+        TargetForm::Authority => *method == Method::CONNECT,
+        TargetForm::Asterisk => *method == Method::OPTIONS,
+    };
+    if !allowed {
+        return Err(ParseError::TargetFormMismatch);
+    }
+
+    Ok((form, scheme, authority, path, query))
+}
+
+/// Splits the Path and the optional Query, which start at the given
+/// Position, and returns their Ranges
+fn split_query(raw: &str, start: usize) -> ((usize, usize), Option<(usize, usize)>) {
+    match raw[start..].find('?') {
+        Some(index) => ((start, start + index), Some((start + index + 1, raw.len()))),
+        None => ((start, raw.len()), None),
+    }
+}
+
+/// Finds the Scheme of an absolute URI, returns the Position
+/// of the `://` that follows it
+fn split_scheme(raw: &str) -> Option<usize> {
+    let scheme_end = raw.find("://")?;
+    let scheme = &raw[..scheme_end];
+
+    let mut chars = scheme.chars();
+    let valid = chars
+        .next()
+        .map(|c| c.is_ascii_alphabetic())
+        .unwrap_or(false)
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.');
+    valid.then_some(scheme_end)
+}
+
+/// Checks if the raw Target is a valid `host:port` Pair
+fn is_authority_form(raw: &str) -> bool {
+    let (host, port) = match raw.rsplit_once(':') {
+        Some(parts) => parts,
+        None => return false,
+    };
+
+    !host.is_empty()
+        && !host.contains('/')
+        && !host.contains('@')
+        && !port.is_empty()
+        && port.bytes().all(|b| b.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_origin() {
+        let target = RequestTarget::parse("/path/?a=b", &Method::GET).unwrap();
+        assert_eq!(TargetForm::Origin, target.form());
+        assert_eq!(None, target.scheme());
+        assert_eq!(None, target.authority());
+        assert_eq!("/path/", target.path());
+        assert_eq!(Some("a=b"), target.query());
+    }
+    #[test]
+    fn parse_absolute() {
+        let target = RequestTarget::parse("http://example.com:80/x?y", &Method::GET).unwrap();
+        assert_eq!(TargetForm::Absolute, target.form());
+        assert_eq!(Some("http"), target.scheme());
+        assert_eq!(Some("example.com:80"), target.authority());
+        assert_eq!("/x", target.path());
+        assert_eq!(Some("y"), target.query());
+
+        let target = RequestTarget::parse("http://example.com", &Method::GET).unwrap();
+        assert_eq!("", target.path());
+        assert_eq!(None, target.query());
+    }
+    #[test]
+    fn parse_authority() {
+        let target = RequestTarget::parse("example.com:443", &Method::CONNECT).unwrap();
+        assert_eq!(TargetForm::Authority, target.form());
+        assert_eq!(Some("example.com:443"), target.authority());
+        assert_eq!("", target.path());
+
+        let target = RequestTarget::parse("[::1]:8080", &Method::CONNECT).unwrap();
+        assert_eq!(Some("[::1]:8080"), target.authority());
+    }
+    #[test]
+    fn parse_asterisk() {
+        let target = RequestTarget::parse("*", &Method::OPTIONS).unwrap();
+        assert_eq!(TargetForm::Asterisk, target.form());
+        assert_eq!("*", target.path());
+    }
+
+    #[test]
+    fn parse_mismatch() {
+        assert_eq!(
+            Err(ParseError::TargetFormMismatch),
+            RequestTarget::parse("example.com:443", &Method::GET)
+        );
+        assert_eq!(
+            Err(ParseError::TargetFormMismatch),
+            RequestTarget::parse("/path", &Method::CONNECT)
+        );
+        assert_eq!(
+            Err(ParseError::TargetFormMismatch),
+            RequestTarget::parse("*", &Method::GET)
+        );
+    }
+    #[test]
+    fn parse_invalid() {
+        assert_eq!(
+            Err(ParseError::InvalidTarget),
+            RequestTarget::parse("", &Method::GET)
+        );
+        assert_eq!(
+            Err(ParseError::InvalidTarget),
+            RequestTarget::parse("/path#fragment", &Method::GET)
+        );
+        assert_eq!(
+            Err(ParseError::InvalidTarget),
+            RequestTarget::parse("path", &Method::GET)
+        );
+        assert_eq!(
+            Err(ParseError::InvalidTarget),
+            RequestTarget::parse("http:///path", &Method::GET)
+        );
+        assert_eq!(
+            Err(ParseError::InvalidTarget),
+            RequestTarget::parse("example.com:https", &Method::CONNECT)
+        );
+    }
+}
@@ -0,0 +1,165 @@
+/// The different HTTP-Versions, as they are send in the
+/// Request-Line or Status-Line, defined in
+/// [RFC 9112 2.3](https://www.rfc-editor.org/rfc/rfc9112#section-2.3)
+///
+/// Versions are compared by their Major and Minor Number, so
+/// `Other(1, 1)` is equal to `Http11`
+#[derive(Debug, Clone, Copy)]
+pub enum Version {
+    /// HTTP/0.9
+    Http09,
+    /// HTTP/1.0
+    Http10,
+    /// HTTP/1.1
+    Http11,
+    /// Any other Version with the given Major and Minor Number,
+    /// like `HTTP/2.0`
+    Other(u8, u8),
+}
+
+impl Version {
+    /// Creates the Version for the given Major and Minor Number
+    pub fn new(major: u8, minor: u8) -> Self {
+        match (major, minor) {
+            (0, 9) => Self::Http09,
+            (1, 0) => Self::Http10,
+            (1, 1) => Self::Http11,
+            _ => Self::Other(major, minor),
+        }
+    }
+
+    /// Parses the raw Version, like `HTTP/1.1`, returns None if
+    /// it does not match `HTTP/DIGIT.DIGIT`
+    pub fn parse(raw_version: &str) -> Option<Version> {
+        Self::parse_bytes(raw_version.as_bytes())
+    }
+
+    pub(crate) fn parse_bytes(raw_version: &[u8]) -> Option<Version> {
+        match raw_version {
+            [b'H', b'T', b'T', b'P', b'/', major, b'.', minor]
+                if major.is_ascii_digit() && minor.is_ascii_digit() =>
+            {
+                Some(Self::new(major - b'0', minor - b'0'))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the Major-Number of the Version
+    pub fn major(&self) -> u8 {
+        match *self {
+            Self::Http09 => 0,
+            Self::Http10 | Self::Http11 => 1,
+            Self::Other(major, _) => major,
+        }
+    }
+    /// Returns the Minor-Number of the Version
+    pub fn minor(&self) -> u8 {
+        match *self {
+            Self::Http09 => 9,
+            Self::Http10 => 0,
+            Self::Http11 => 1,
+            Self::Other(_, minor) => minor,
+        }
+    }
+
+    /// Serializes the Version into the given Buffer
+    pub fn serialize(&self, buf: &mut Vec<u8>) {
+        match *self {
+            Self::Http09 => buf.extend_from_slice(b"HTTP/0.9"),
+            Self::Http10 => buf.extend_from_slice(b"HTTP/1.0"),
+            Self::Http11 => buf.extend_from_slice(b"HTTP/1.1"),
+            Self::Other(major, minor) => {
+                buf.extend_from_slice(format!("HTTP/{}.{}", major, minor).as_bytes())
+            }
+        }
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        (self.major(), self.minor()) == (other.major(), other.minor())
+    }
+}
+
+impl Eq for Version {}
+
+impl std::hash::Hash for Version {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        (self.major(), self.minor()).hash(state);
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.major(), self.minor()).cmp(&(other.major(), other.minor()))
+    }
+}
+
+impl std::fmt::Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "HTTP/{}.{}", self.major(), self.minor())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_known() {
+        assert_eq!(Some(Version::Http09), Version::parse("HTTP/0.9"));
+        assert_eq!(Some(Version::Http10), Version::parse("HTTP/1.0"));
+        assert_eq!(Some(Version::Http11), Version::parse("HTTP/1.1"));
+    }
+    #[test]
+    fn parse_other() {
+        assert_eq!(Some(Version::Other(2, 0)), Version::parse("HTTP/2.0"));
+        assert_eq!(Version::Http11, Version::new(1, 1));
+    }
+    #[test]
+    fn parse_invalid() {
+        assert_eq!(None, Version::parse("HXXP/9"));
+        assert_eq!(None, Version::parse("http/1.1"));
+        assert_eq!(None, Version::parse("HTTP/1.10"));
+        assert_eq!(None, Version::parse("HTTP/1"));
+        assert_eq!(None, Version::parse("HTTP/x.1"));
+        assert_eq!(None, Version::parse(""));
+    }
+
+    #[test]
+    fn serialize() {
+        let mut buf = Vec::new();
+        Version::Http11.serialize(&mut buf);
+        Version::Other(2, 0).serialize(&mut buf);
+        assert_eq!("HTTP/1.1HTTP/2.0".as_bytes(), &buf[..]);
+        assert_eq!("HTTP/1.0", Version::Http10.to_string());
+    }
+
+    #[test]
+    fn ordering() {
+        assert!(Version::Http09 < Version::Http10);
+        assert!(Version::Http10 < Version::Http11);
+        assert!(Version::Http11 < Version::Other(2, 0));
+        assert!(Version::Other(0, 8) < Version::Http09);
+    }
+    #[test]
+    fn other_equals_known() {
+        use std::collections::HashSet;
+
+        assert_eq!(Version::Http11, Version::Other(1, 1));
+        assert_eq!(
+            std::cmp::Ordering::Equal,
+            Version::Other(1, 0).cmp(&Version::Http10)
+        );
+
+        let versions: HashSet<Version> = [Version::Http11, Version::Other(1, 1)].into();
+        assert_eq!(1, versions.len());
+    }
+}